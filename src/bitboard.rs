@@ -0,0 +1,433 @@
+use std::fmt;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXorAssign, Not, Shl, Shr};
+use std::sync::Arc;
+
+use crate::board::{Board, DateError};
+use crate::layout::BoardLayout;
+use crate::piece::OrientedPiece;
+
+/// Largest number of cells a [`BitBoard`] can have, one per bit of a u128
+pub const MAX_CELLS: usize = u128::CELLS;
+
+/// What the cells of a [`BitBoard`] are kept in, one per bit. Boards of up to 64 cells use a
+/// u64, which is quite a bit faster than a u128.
+pub trait Bits:
+    Copy
+    + Eq
+    + fmt::Debug
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + Not<Output = Self>
+    + Shl<usize, Output = Self>
+    + Shr<usize, Output = Self>
+    + BitAndAssign
+    + BitOrAssign
+    + BitXorAssign
+{
+    /// Number of bits
+    const CELLS: usize;
+    /// No cell at all
+    const ZERO: Self;
+    /// Only the first cell
+    const ONE: Self;
+
+    /// Number of cells set
+    fn count_ones(self) -> u32;
+    /// Index of the first cell set
+    fn trailing_zeros(self) -> u32;
+    /// Only the first cell set
+    fn lowest(self) -> Self;
+}
+
+macro_rules! bits {
+    ($($word:ty),*) => {$(
+        impl Bits for $word {
+            const CELLS: usize = <$word>::BITS as usize;
+            const ZERO: $word = 0;
+            const ONE: $word = 1;
+
+            fn count_ones(self) -> u32 {
+                <$word>::count_ones(self)
+            }
+
+            fn trailing_zeros(self) -> u32 {
+                <$word>::trailing_zeros(self)
+            }
+
+            fn lowest(self) -> $word {
+                self & self.wrapping_neg()
+            }
+        }
+    )*};
+}
+
+bits!(u64, u128);
+
+// cells are numbered line by line from the top left, cell n is bit n
+fn bit<B: Bits>(width: usize, column: usize, line: usize) -> B {
+    B::ONE << (line * width + column)
+}
+
+fn column_mask<B: Bits>(width: usize, height: usize, column: usize) -> B {
+    (0..height).fold(B::ZERO, |mask, line| mask | bit(width, column, line))
+}
+
+/// An [`OrientedPiece`] shifted so that the first square of its top line lands on each cell of
+/// the board
+pub struct PieceMasks<B: Bits = u64> {
+    /// Id of the piece
+    pub id: char,
+    /// Number of squares of the piece
+    pub size: usize,
+    // 0 when the piece would go over the edge of the board
+    masks: Vec<B>,
+}
+
+impl<B: Bits> PieceMasks<B> {
+    /// Computes the masks of the piece for every cell of a board of that size
+    pub fn new(id: char, piece: &OrientedPiece, width: usize, height: usize) -> PieceMasks<B> {
+        let squares = piece.pattern.iter().flatten();
+        let mut result = PieceMasks {
+            id,
+            size: squares.filter(|&&square| square != '0').count(),
+            masks: vec![B::ZERO; width * height],
+        };
+        let top_index = usize::from(piece.top_index);
+
+        for line in 0..height {
+            'columns: for column in top_index..width {
+                let mut mask = B::ZERO;
+                for (j, row) in piece.pattern.iter().enumerate() {
+                    for (i, &square) in row.iter().enumerate() {
+                        if square == '0' {
+                            continue;
+                        }
+                        let (x, y) = (column - top_index + i, line + j);
+                        if x >= width || y >= height {
+                            continue 'columns;
+                        }
+                        mask |= bit(width, x, y);
+                    }
+                }
+                result.masks[line * width + column] = mask;
+            }
+        }
+
+        result
+    }
+}
+
+/// Same as [`Board`] but each cell is a bit of a [`Bits`], a piece is placed by OR-ing its mask
+/// and removed by XOR-ing it back. The table of chars is only rebuilt to be displayed.
+#[derive(Debug, Clone)]
+pub struct BitBoard<B: Bits = u64> {
+    width: usize,
+    height: usize,
+    occupied: B,
+    blocked: B,
+    placed: Vec<(char, B)>,
+    layout: Arc<BoardLayout>,
+    all_cells: B,
+    // used to stop the flood fill in is_solvable from wrapping around to the next line
+    first_column: B,
+    last_column: B,
+}
+
+impl BitBoard {
+    /// An empty calendar
+    pub fn new() -> BitBoard {
+        BitBoard::from(&Board::new())
+    }
+}
+
+impl<B: Bits> BitBoard<B> {
+    /// Number of columns
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of lines
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// See [`Board::set_date`]
+    pub fn set_date(&mut self, week_day: u8, day: u8, month: u8) -> Result<(), DateError> {
+        let mut b = self.to_board();
+        b.set_date(week_day, day, month)?;
+        let date = BitBoard::<B>::from(&b);
+        self.blocked = date.blocked;
+        self.occupied |= date.blocked;
+        Ok(())
+    }
+
+    /// Whether every cell is covered
+    pub fn is_full(&self) -> bool {
+        self.occupied == self.all_cells
+    }
+
+    /// The first free cell reading line by line, as `line * width + column`
+    pub fn first_free_cell(&self) -> Option<usize> {
+        let free = !self.occupied & self.all_cells;
+        (free != B::ZERO).then(|| free.trailing_zeros() as usize)
+    }
+
+    /// Same as [`BitBoard::place_piece_on_top_left`] with the masks already computed
+    pub fn place(&mut self, piece: &PieceMasks<B>) -> bool {
+        let Some(cell) = self.first_free_cell() else {
+            return false;
+        };
+        let mask = piece.masks[cell];
+        if mask == B::ZERO || mask & self.occupied != B::ZERO {
+            return false;
+        }
+        self.occupied |= mask;
+        self.placed.push((piece.id, mask));
+        true
+    }
+
+    /// See [`Board::place_piece_on_top_left`]
+    pub fn place_piece_on_top_left(&mut self, piece: &OrientedPiece) -> bool {
+        // the first square of the top line always holds the id of the piece
+        let id = piece.pattern[0][usize::from(piece.top_index)];
+        self.place(&PieceMasks::new(id, piece, self.width, self.height))
+    }
+
+    /// Frees the cells covered by a piece
+    pub fn remove_piece(&mut self, piece_id: char) {
+        if let Some(index) = self.placed.iter().rposition(|&(id, _)| id == piece_id) {
+            self.occupied ^= self.placed[index].1;
+            self.placed.remove(index);
+        }
+    }
+
+    /// See [`Board::is_solvable`]
+    pub fn is_solvable(&self) -> bool {
+        self.free_areas()
+            .all(|area| area.count_ones().is_multiple_of(5))
+    }
+
+    /// Quick check that every free area of the board could be filled with some of the pieces
+    /// of those sizes, on its own
+    pub fn is_solvable_with(&self, piece_sizes: impl IntoIterator<Item = usize>) -> bool {
+        // bit n is set when some of the pieces add up to n cells, larger areas are not checked
+        let mut sums: u128 = 1;
+        for size in piece_sizes {
+            sums |= sums.checked_shl(size as u32).unwrap_or(0);
+        }
+        self.free_areas().all(|area| {
+            sums.checked_shr(area.count_ones())
+                .is_none_or(|sums| sums & 1 == 1)
+        })
+    }
+
+    // the free cells that touch each other, one area after the other
+    fn free_areas(&self) -> impl Iterator<Item = B> + '_ {
+        let mut free = !self.occupied & self.all_cells;
+        std::iter::from_fn(move || {
+            if free == B::ZERO {
+                return None;
+            }
+            // grow the area from the first free cell until it stops changing
+            let mut area = free.lowest();
+            loop {
+                let grown = (area
+                    | ((area << 1) & !self.first_column)
+                    | ((area >> 1) & !self.last_column)
+                    | (area << self.width)
+                    | (area >> self.width))
+                    & free;
+                if grown == area {
+                    break;
+                }
+                area = grown;
+            }
+            free &= !area;
+            Some(area)
+        })
+    }
+
+    /// The same board as a table of chars
+    pub fn to_board(&self) -> Board {
+        let mut b = Board::from_layout(self.layout.clone());
+        for line in 0..self.height {
+            for column in 0..self.width {
+                let cell: B = bit(self.width, column, line);
+                if self.blocked & cell != B::ZERO {
+                    b.set_cell(column, line, 'X');
+                } else if let Some(&(id, _)) =
+                    self.placed.iter().find(|&&(_, m)| m & cell != B::ZERO)
+                {
+                    b.set_cell(column, line, id);
+                } else {
+                    b.set_cell(column, line, '0');
+                }
+            }
+        }
+        b
+    }
+}
+
+impl Default for BitBoard {
+    fn default() -> BitBoard {
+        BitBoard::new()
+    }
+}
+
+impl<B: Bits> From<&Board> for BitBoard<B> {
+    fn from(b: &Board) -> BitBoard<B> {
+        let (width, height) = (b.width(), b.height());
+        assert!(
+            width * height <= B::CELLS,
+            "this bit board has at most {} cells",
+            B::CELLS
+        );
+        let all_cells = !B::ZERO >> (B::CELLS - width * height);
+        let mut result = BitBoard {
+            width,
+            height,
+            occupied: B::ZERO,
+            blocked: B::ZERO,
+            placed: Vec::new(),
+            layout: b.layout().clone(),
+            all_cells,
+            first_column: column_mask(width, height, 0),
+            last_column: column_mask(width, height, width - 1),
+        };
+        for line in 0..height {
+            for column in 0..width {
+                let cell: B = bit(width, column, line);
+                match b.cell(column, line) {
+                    '0' => continue,
+                    'X' => result.blocked |= cell,
+                    id => match result.placed.iter_mut().find(|(p, _)| *p == id) {
+                        Some((_, mask)) => *mask |= cell,
+                        None => result.placed.push((id, cell)),
+                    },
+                }
+                result.occupied |= cell;
+            }
+        }
+        result
+    }
+}
+
+impl<B: Bits> fmt::Display for BitBoard<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.to_board())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::piece;
+
+    fn same<B: Bits>(bits: &BitBoard<B>, b: &Board) {
+        assert_eq!(format!("{:?}", bits.to_board()), format!("{b:?}"));
+    }
+
+    #[test]
+    fn new_and_dates() {
+        same(&BitBoard::new(), &Board::new());
+        for (week_day, day, month) in [(1, 1, 1), (4, 13, 8), (7, 14, 12)] {
+            let mut bits = BitBoard::new();
+            let mut b = Board::new();
+            bits.set_date(week_day, day, month).unwrap();
+            b.set_date(week_day, day, month).unwrap();
+            same(&bits, &b);
+            assert!(!bits.is_full());
+        }
+
+        let mut bits = BitBoard::new();
+        assert_eq!(bits.set_date(1, 1, 0), Err(DateError::InvalidMonth(0)));
+        bits.set_date(1, 1, 1).unwrap();
+        assert_eq!(
+            bits.set_date(1, 2, 1),
+            Err(DateError::AlreadyCovered("JAN".to_string()))
+        );
+    }
+
+    #[test]
+    fn place_and_remove_like_board() {
+        let mut bits = BitBoard::new();
+        let mut b = Board::new();
+        bits.set_date(1, 1, 1).unwrap();
+        b.set_date(1, 1, 1).unwrap();
+
+        for id in ['f', 'l', 'q', 'y', 'i', 'z'] {
+            for oriented_piece in piece::get_piece(id).unwrap().possible_pieces {
+                assert_eq!(
+                    bits.place_piece_on_top_left(&oriented_piece),
+                    b.place_piece_on_top_left(&oriented_piece)
+                );
+                same(&bits, &b);
+                assert_eq!(bits.is_solvable(), b.is_solvable());
+                if bits.placed.iter().any(|&(p, _)| p == id) {
+                    break;
+                }
+            }
+        }
+
+        bits.remove_piece('q');
+        b.remove_piece('q');
+        same(&bits, &b);
+        assert_eq!(bits.is_solvable(), b.is_solvable());
+    }
+
+    #[test]
+    fn solvable() {
+        let mut bits = BitBoard::new();
+        bits.set_date(1, 2, 3).unwrap();
+        assert!(bits.is_solvable());
+
+        // the top left corner is cut off from the rest of the board
+        let mut b = Board::new();
+        b.set_cell(2, 0, 'X');
+        b.set_cell(0, 1, 'X');
+        b.set_cell(1, 1, 'X');
+        assert!(!BitBoard::<u64>::from(&b).is_solvable());
+        assert!(!BitBoard::<u128>::from(&b).is_solvable());
+        assert!(!b.is_solvable());
+        assert!(!b.is_solvable_with([5; 10]));
+        // the 2 cells of the corner and the 48 others can be filled with pieces of other sizes
+        assert!(BitBoard::<u64>::from(&b).is_solvable_with([2, 6, 6, 6, 6, 6, 6, 6, 6]));
+        assert!(!BitBoard::<u64>::from(&b).is_solvable_with([2, 6, 6, 6, 6, 6, 6, 6, 5]));
+    }
+
+    #[test]
+    fn lines_do_not_wrap_around() {
+        // the last cell of a line is not next to the first cell of the following line
+        // 3 free cells at the end of the first line, and 2 at the start of the second one
+        // that are joined to the rest of the board
+        let mut b = Board::new();
+        for column in 0..b.width() - 3 {
+            b.set_cell(column, 0, 'X');
+        }
+        for column in 2..b.width() {
+            b.set_cell(column, 1, 'X');
+        }
+        let bits: BitBoard = BitBoard::from(&b);
+        assert_eq!(bits.is_solvable(), b.is_solvable());
+        assert!(!bits.is_solvable());
+        assert!(!BitBoard::<u128>::from(&b).is_solvable());
+    }
+
+    #[test]
+    fn larger_than_a_u64() {
+        // 66 cells, the last line is cut off from the rest
+        let mut b = Board::with_size(11, 6).unwrap();
+        for column in 0..11 {
+            b.set_cell(column, 4, 'X');
+        }
+        let mut bits: BitBoard<u128> = BitBoard::from(&b);
+        assert_eq!(bits.first_free_cell(), Some(0));
+        assert!(!bits.is_solvable_with([5; 10]));
+        assert!(bits.is_solvable_with([11; 5]));
+
+        let u = piece::get_piece('u').unwrap();
+        assert!(bits.place_piece_on_top_left(&u.possible_pieces[0]));
+        assert!(b.place_piece_on_top_left(&u.possible_pieces[0]));
+        same(&bits, &b);
+    }
+}
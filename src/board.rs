@@ -1,102 +1,176 @@
-use std::{collections::VecDeque, fmt};
+use std::{fmt, sync::Arc};
 
+use chrono::{Datelike, NaiveDate};
+
+use crate::bitboard::{BitBoard, Bits};
+use crate::date::Date;
+use crate::layout::{self, BoardLayout, Cell, LayoutError, MONTHS, WEEK_DAYS};
 use crate::piece::OrientedPiece;
 
-const NUM_LINES: usize = 6;
-const NUM_COLUMNS: usize = 9;
+/// Why a date could not be set on a board
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateError {
+    /// The week day is not between 1 (Monday) and 7 (Sunday)
+    InvalidWeekDay(u8),
+    /// The day is not between 1 and 31
+    InvalidDay(u8),
+    /// The month is not between 1 and 12
+    InvalidMonth(u8),
+    /// The day is not in that month in any year, like Feb 30th
+    NoSuchDate {
+        /// Day of the month
+        day: u8,
+        /// 1 for January, 12 for December
+        month: u8,
+    },
+    /// The layout of the board does not have that label
+    UnknownLabel(String),
+    /// The cell with that label is already covered or blocked
+    AlreadyCovered(String),
+}
+
+impl fmt::Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            DateError::InvalidWeekDay(week_day) => {
+                write!(
+                    f,
+                    "week day {week_day} should be from 1 (Monday) to 7 (Sunday)"
+                )
+            }
+            DateError::InvalidDay(day) => write!(f, "day {day} should be between 1 and 31"),
+            DateError::InvalidMonth(month) => {
+                write!(f, "month {month} should be between 1 and 12")
+            }
+            DateError::NoSuchDate { day, month } => {
+                let month = chrono::Month::try_from(*month).map_or("", |m| m.name());
+                write!(f, "there is no day {day} in {month}")
+            }
+            DateError::UnknownLabel(label) => write!(f, "{label} is not on the board"),
+            DateError::AlreadyCovered(label) => write!(f, "{label} is already covered"),
+        }
+    }
+}
 
-#[derive(Debug, Clone)]
+impl std::error::Error for DateError {}
+
+/// The calendar, each cell holds the id of the piece covering it, `'0'` when it is free or
+/// `'X'` when it cannot be covered (outside of the calendar or part of the date)
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Board {
-    table: [[char; NUM_COLUMNS]; NUM_LINES],
+    table: Vec<Vec<char>>,
+    layout: Arc<BoardLayout>,
 }
 
 impl Board {
+    /// An empty calendar
     pub fn new() -> Board {
+        Board::from_layout(BoardLayout::weekday())
+    }
+
+    /// An empty rectangle without any date on it, see [`BoardLayout::rectangle`]
+    pub fn with_size(width: usize, height: usize) -> Result<Board, LayoutError> {
+        Ok(Board::from_layout(BoardLayout::rectangle(width, height)?))
+    }
+
+    /// The calendar with the cells of that date blocked
+    pub fn for_date(date: NaiveDate) -> Board {
+        let mut b = Board::new();
+        b.set_date_from(date)
+            .expect("every date is on the calendar");
+        b
+    }
+
+    /// An empty board with that layout, the cells outside of it are blocked
+    pub fn from_layout(layout: impl Into<Arc<BoardLayout>>) -> Board {
+        let layout = layout.into();
         let mut new_board = Board {
-            table: [['0'; NUM_COLUMNS]; NUM_LINES],
+            table: vec![vec!['0'; layout.width()]; layout.height()],
+            layout,
         };
-        new_board.table[NUM_LINES - 1][NUM_COLUMNS - 1] = 'X';
+        for (line, row) in new_board.table.iter_mut().enumerate() {
+            for (column, cell) in row.iter_mut().enumerate() {
+                if new_board.layout.cell(column, line) == &Cell::Blocked {
+                    *cell = 'X';
+                }
+            }
+        }
 
         new_board
     }
 
-    // week_day is 1 for Monday, 7 for Sunday
-    pub fn set_date(&mut self, week_day: u8, day: u8, month: u8) {
-        let week_coords: (usize, usize) = match week_day {
-            1 => (7, 0),
-            2 => (8, 0),
-            3 => (7, 1),
-            4 => (7, 2),
-            5 => (7, 3),
-            6 => (8, 3),
-            7 => (8, 4),
-            _ => {
-                panic!("week_day should be from 1 (Monday) to 7 (Sunday)")
-            }
-        };
-        self.table[week_coords.1][week_coords.0] = 'X';
-
-        let day_coords: (usize, usize) = match day {
-            1 => (4, 0),
-            2 => (5, 0),
-            3 => (6, 0),
-            4 => (1, 1),
-            5 => (2, 1),
-            6 => (3, 1),
-            7 => (4, 1),
-            8 => (5, 1),
-            9 => (6, 1),
-            10 => (1, 2),
-            11 => (2, 2),
-            12 => (3, 2),
-            13 => (4, 2),
-            14 => (7, 5),
-            15 => (6, 2),
-            16 => (1, 3),
-            17 => (2, 3),
-            18 => (3, 3),
-            19 => (4, 3),
-            20 => (5, 3),
-            21 => (6, 3),
-            22 => (1, 4),
-            23 => (2, 4),
-            24 => (3, 4),
-            25 => (4, 4),
-            26 => (5, 4),
-            27 => (6, 4),
-            28 => (4, 5),
-            29 => (5, 5),
-            30 => (6, 5),
-            31 => (5, 2),
-            _ => {
-                panic!("day should be between 1 and 31")
-            }
-        };
-        self.table[day_coords.1][day_coords.0] = 'X';
-
-        let month_coords: (usize, usize) = match month {
-            1 => (0, 0),
-            2 => (1, 0),
-            3 => (2, 0),
-            4 => (3, 0),
-            5 => (0, 1),
-            6 => (0, 2),
-            7 => (0, 3),
-            8 => (0, 4),
-            9 => (0, 5),
-            10 => (1, 5),
-            11 => (2, 5),
-            12 => (3, 5),
-            _ => {
-                panic!("month should be between 1 and 12")
+    /// Blocks the cells of the date, week_day is 1 for Monday, 7 for Sunday. The date has to
+    /// exist in some year, see [`Date::new`] and [`Board::set_date_to`].
+    pub fn set_date(&mut self, week_day: u8, day: u8, month: u8) -> Result<(), DateError> {
+        self.set_date_to(Date::new(week_day, day, month)?)
+    }
+
+    /// Blocks the cells of a date chrono knows, see [`Board::set_date_to`]
+    pub fn set_date_from(&mut self, date: impl Datelike) -> Result<(), DateError> {
+        self.set_date_to(Date::from_datelike(&date))
+    }
+
+    /// Blocks the cells of the date. The week day is left out on layouts without week days.
+    /// Nothing is blocked if any of it is not on the board or already covered.
+    pub fn set_date_to(&mut self, date: Date) -> Result<(), DateError> {
+        let mut labels = vec![
+            date.day().to_string(),
+            MONTHS[usize::from(date.month()) - 1].to_string(),
+        ];
+        // some calendars only have the days and the months
+        if self.layout.has_week_days() {
+            labels.push(WEEK_DAYS[usize::from(date.week_day()) - 1].to_string());
+        }
+        let mut cells = Vec::new();
+        for label in labels {
+            let (column, line) = self
+                .layout
+                .find(&label)
+                .ok_or_else(|| DateError::UnknownLabel(label.clone()))?;
+            if self.table[line][column] != '0' {
+                return Err(DateError::AlreadyCovered(label));
             }
-        };
-        self.table[month_coords.1][month_coords.0] = 'X';
+            cells.push((column, line));
+        }
+        for (column, line) in cells {
+            self.table[line][column] = 'X';
+        }
+        Ok(())
+    }
+
+    /// Where the months, days and week days are
+    pub fn layout(&self) -> &Arc<BoardLayout> {
+        &self.layout
+    }
+
+    /// Number of columns
+    pub fn width(&self) -> usize {
+        self.layout.width()
     }
 
+    /// Number of lines
+    pub fn height(&self) -> usize {
+        self.layout.height()
+    }
+
+    /// What is on a cell, see [`Board`]
+    pub fn cell(&self, column: usize, line: usize) -> char {
+        self.table[line][column]
+    }
+
+    /// What is written on a cell of the calendar, whether or not a piece covers it
+    pub fn label(&self, column: usize, line: usize) -> Option<&str> {
+        self.layout.label(column, line)
+    }
+
+    pub(crate) fn set_cell(&mut self, column: usize, line: usize, value: char) {
+        self.table[line][column] = value;
+    }
+
+    /// Whether every cell is covered
     pub fn is_full(&self) -> bool {
-        for row in self.table {
-            for space in row {
+        for row in &self.table {
+            for &space in row {
                 if space == '0' {
                     return false;
                 }
@@ -105,12 +179,12 @@ impl Board {
         true
     }
 
-    fn find_first_free_space(&self, left_offset: usize) -> Result<(usize, usize),()> {
+    fn find_first_free_space(&self) -> Result<(usize, usize),()> {
         // first find the top-left empty space on the board
         let mut top_left: (usize, usize) = (0, 0);
 
-        '_outer: for j in 0..NUM_LINES {
-            for i in left_offset..NUM_COLUMNS {
+        '_outer: for j in 0..self.height() {
+            for i in 0..self.width() {
                 if self.table[j][i] == '0' {
                     top_left.0 = i;
                     top_left.1 = j;
@@ -126,31 +200,55 @@ impl Board {
         Ok(top_left)
     }
 
+    /// Puts the piece so that the first cell of its top line covers the first free cell of
+    /// the board, reading line by line. Returns false, leaving the board untouched, if it does
+    /// not fit there.
     pub fn place_piece_on_top_left(&mut self, piece: &OrientedPiece) -> bool {
-        let top_left = match self.find_first_free_space(usize::from(piece.top_index)) {
+        let top_left = match self.find_first_free_space() {
             Ok(r) => r,
             Err(_) => { return false;}
         };
 
+        // the first cell of the piece's top row has to cover the first free space,
+        // otherwise that space is left for another piece and we would find the same
+        // solution again in a different order
+        if top_left.0 < usize::from(piece.top_index) {
+            return false;
+        }
+
         let offset: (usize, usize) = (top_left.0 - usize::from(piece.top_index), top_left.1);
+        self.place_piece_at(piece, offset.0, offset.1)
+    }
 
+    /// Whether every square of the piece would be on a free cell of the board, with the top
+    /// left corner of the box around the piece on that cell
+    pub fn fits_at(&self, piece: &OrientedPiece, column: usize, line: usize) -> bool {
         let piece_rows: usize = piece.pattern.len();
         let piece_cols = piece.pattern[0].len();
         for j in 0..piece_rows {
             for i in 0..piece_cols {
-                if piece.pattern[j][i] != '0' && (offset.0 + i >= NUM_COLUMNS || offset.1 + j >= NUM_LINES) {
+                if piece.pattern[j][i] != '0' && (column + i >= self.width() || line + j >= self.height()) {
                     return false;
                 }
-                if piece.pattern[j][i] != '0' && self.table[offset.1 + j][offset.0 + i] != '0' {
+                if piece.pattern[j][i] != '0' && self.table[line + j][column + i] != '0' {
                     return false;
                 }
             }
         }
+        true
+    }
 
-        for j in 0..piece_rows {
-            for i in 0..piece_cols {
-                if piece.pattern[j][i] != '0' {
-                    self.table[offset.1 + j][offset.0 + i] = piece.pattern[j][i];
+    /// Puts the piece with the top left corner of the box around it on that cell. Returns
+    /// false, leaving the board untouched, if it does not fit there, see [`Board::fits_at`].
+    pub fn place_piece_at(&mut self, piece: &OrientedPiece, column: usize, line: usize) -> bool {
+        if !self.fits_at(piece, column, line) {
+            return false;
+        }
+
+        for (j, row) in piece.pattern.iter().enumerate() {
+            for (i, &square) in row.iter().enumerate() {
+                if square != '0' {
+                    self.table[line + j][column + i] = square;
                 }
             }
         }
@@ -158,9 +256,10 @@ impl Board {
         true
     }
 
+    /// Frees the cells covered by a piece
     pub fn remove_piece(&mut self, piece_id: char) {
-        for j in 0..NUM_LINES {
-            for i in 0..NUM_COLUMNS {
+        for j in 0..self.height() {
+            for i in 0..self.width() {
                 if self.table[j][i] == piece_id {
                     self.table[j][i] = '0';
                 }
@@ -168,72 +267,48 @@ impl Board {
         }
     }
 
-    pub fn is_solvable(&mut self) -> bool {
-        let mut coords: VecDeque<(usize, usize)> = VecDeque::new();
-        let mut area: u8 = 1;
-        let mut area_size = 0;
-
-        while !self.is_full() {
-            let top_left = self.find_first_free_space(0).unwrap();
-            coords.push_back(top_left);
-            while !coords.is_empty() {
-                let current = coords.pop_front().unwrap();
-                if self.table[current.1][current.0] != '0' {
-                    continue;
-                }
-                if current.0 > 0 && self.table[current.1][current.0 - 1] == '0' {
-                    coords.push_back((current.0 - 1, current.1));
-                }
-                if current.0 < NUM_COLUMNS - 1 && self.table[current.1][current.0 + 1] == '0' {
-                    coords.push_back((current.0 + 1, current.1));
-                }
-                if current.1 > 0 && self.table[current.1 - 1][current.0] == '0' {
-                    coords.push_back((current.0, current.1 - 1));
-                }
-                if current.1 < NUM_LINES - 1 && self.table[current.1 + 1][current.0] == '0' {
-                    coords.push_back((current.0, current.1 + 1));
-                }
-                self.table[current.1][current.0] = char::from_digit(area as u32, 10).unwrap();
-                area_size += 1;
-            }
-            if area_size % 5 != 0 {
-                // clean-up
-                loop {
-                    self.remove_piece(char::from_digit(area as u32, 10).unwrap());
-                    area -= 1;
-                    if area == 0 {
-                        break;
-                    }
-                }
-                return false;
-            }
-
-            area += 1;
-            area_size = 0;
-            if area == 10 {
-                panic!("Did we really divide the boards in so many areas?")
-            }
+    /// Quick check that every free area of the board could still be filled with pentominoes,
+    /// that is that their sizes are multiples of 5
+    pub fn is_solvable(&self) -> bool {
+        // the areas are found on the bits, marking them on the table could clash with the id of
+        // a piece
+        if self.width() * self.height() <= u64::CELLS {
+            BitBoard::<u64>::from(self).is_solvable()
+        } else {
+            BitBoard::<u128>::from(self).is_solvable()
         }
-        // clean-up
-        loop {
-            self.remove_piece(char::from_digit(area as u32, 10).unwrap());
-            area -= 1;
-            if area == 0 {
-                break;
-            }
+    }
+
+    /// Quick check that every free area of the board could be filled with some of the pieces
+    /// of those sizes, on its own. The board is looked at as a [`BitBoard`].
+    pub fn is_solvable_with(&self, piece_sizes: impl IntoIterator<Item = usize>) -> bool {
+        if self.width() * self.height() <= u64::CELLS {
+            BitBoard::<u64>::from(self).is_solvable_with(piece_sizes)
+        } else {
+            BitBoard::<u128>::from(self).is_solvable_with(piece_sizes)
         }
-        true
     }
 }
 
+impl Default for Board {
+    fn default() -> Board {
+        Board::new()
+    }
+}
+
+// width of the inside of a cell on the drawing, room for "Jan" or "Mon"
+const LABEL_WIDTH: usize = 3;
+
+/// Draws the board with lines around the pieces, thick ones around the board and the date,
+/// and the labels of the cells no piece covers
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         // first is top line going clockwise
         // 0 = nothing
         // 1 = thin line
         // 2 = thick line
-        let mut lines: [[[usize; 4]; NUM_COLUMNS + 1]; NUM_LINES + 1] =
-            [[[0; 4]; NUM_COLUMNS + 1]; NUM_LINES + 1];
+        let (width, height) = (self.width(), self.height());
+        let mut lines: Vec<Vec<[usize; 4]>> = vec![vec![[0; 4]; width + 1]; height + 1];
 
         // neighbords start with top left going clockwise
         fn get_lines(neighbors: (char, char, char, char)) -> [usize; 4] {
@@ -271,52 +346,22 @@ impl fmt::Display for Board {
             result
         }
 
-        for i in 0..NUM_LINES + 1 {
-            for j in 0..NUM_COLUMNS + 1 {
-                match (i, j) {
-                    (0, 0) => {
-                        // top left
-                        lines[i][j] = get_lines(('X', 'X', self.table[i][j], 'X'));
-                    }
-                    (0, NUM_COLUMNS) => {
-                        // top right
-                        lines[i][j] = get_lines(('X', 'X', 'X', self.table[i][j - 1]));
-                    }
-                    (NUM_LINES, 0) => {
-                        // bottom left
-                        lines[i][j] = get_lines(('X', self.table[i - 1][j], 'X', 'X'));
-                    }
-                    (NUM_LINES, NUM_COLUMNS) => {
-                        //bottom right
-                        lines[i][j] = get_lines((self.table[i - 1][j - 1], 'X', 'X', 'X'));
-                    }
-                    (NUM_LINES, _) => {
-                        // bottom
-                        lines[i][j] =
-                            get_lines((self.table[i - 1][j - 1], self.table[i - 1][j], 'X', 'X'));
-                    }
-                    (0, _) => {
-                        // top
-                        lines[i][j] = get_lines(('X', 'X', self.table[i][j], self.table[i][j - 1]));
-                    }
-                    (_, 0) => {
-                        // left
-                        lines[i][j] = get_lines(('X', self.table[i - 1][j], self.table[i][j], 'X'));
-                    }
-                    (_, NUM_COLUMNS) => {
-                        // right
-                        lines[i][j] =
-                            get_lines((self.table[i - 1][j - 1], 'X', 'X', self.table[i][j - 1]));
-                    }
-                    _ => {
-                        lines[i][j] = get_lines((
-                            self.table[i - 1][j - 1],
-                            self.table[i - 1][j],
-                            self.table[i][j],
-                            self.table[i][j - 1],
-                        ));
-                    }
-                }
+        // the cell whose bottom right corner is at (i, j), outside of the board is blocked
+        let before = |i: usize, j: usize| -> char {
+            if i == 0 || j == 0 || i > height || j > width {
+                'X'
+            } else {
+                self.table[i - 1][j - 1]
+            }
+        };
+        for (i, row) in lines.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = get_lines((
+                    before(i, j),
+                    before(i, j + 1),
+                    before(i + 1, j + 1),
+                    before(i + 1, j),
+                ));
             }
         }
         // https://en.wikipedia.org/wiki/Box-drawing_characters
@@ -351,11 +396,33 @@ impl fmt::Display for Board {
         ];
 
         let mut drawing: String = String::new();
-        for i in 0..NUM_LINES + 1 {
-            for j in 0..NUM_COLUMNS + 1 {
-                drawing.push(
-                    BOX_CHARS[lines[i][j][0]][lines[i][j][1]][lines[i][j][2]][lines[i][j][3]],
-                );
+        for (i, row) in lines.iter().enumerate() {
+            // the corners and the sides between them
+            for (j, l) in row.iter().enumerate() {
+                drawing.push(BOX_CHARS[l[0]][l[1]][l[2]][l[3]]);
+                if j < width {
+                    let side = [' ', '─', '━'][l[1]];
+                    drawing.extend(std::iter::repeat_n(side, LABEL_WIDTH));
+                }
+            }
+            drawing.push('\n');
+            if i == height {
+                break;
+            }
+
+            // the sides going down and what is written in the cells
+            for (j, l) in row.iter().enumerate() {
+                drawing.push([' ', '│', '┃'][l[2]]);
+                if j == width {
+                    continue;
+                }
+                let label = self.label(j, i).map(|label| {
+                    format!("{:^LABEL_WIDTH$.LABEL_WIDTH$}", layout::title_case(label))
+                });
+                match (self.table[i][j], label) {
+                    ('X' | '0', Some(label)) => drawing.push_str(&label),
+                    _ => drawing.extend(std::iter::repeat_n(' ', LABEL_WIDTH)),
+                }
             }
             drawing.push('\n');
         }
@@ -367,6 +434,45 @@ impl fmt::Display for Board {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::piece::{Piece, Rules};
+    use crate::solver::{Backtracking, Solver};
+
+    #[test]
+    fn drawing() {
+        let mut b = Board::with_size(3, 2).unwrap();
+        b.set_cell(0, 0, 'a');
+        b.set_cell(1, 0, 'a');
+        b.set_cell(0, 1, 'b');
+        b.set_cell(2, 1, 'X');
+        assert_eq!(
+            b.to_string(),
+            [
+                "┏━━━━━━━┯━━━┓",
+                "┃       │   ┃",
+                "┠───┬───╆━━━┛",
+                "┃   │   ┃    ",
+                "┗━━━┷━━━┛    ",
+                "",
+            ]
+            .join("\n")
+        );
+
+        // only the labels of the date can be seen
+        let mut b = Board::new();
+        b.set_date(4, 13, 8).unwrap();
+        let solution = Backtracking::default()
+            .solve_first(&b, &crate::piece::all_pieces())
+            .unwrap();
+        let text = solution.to_string();
+        assert_eq!(text.lines().count(), 13);
+        for label in ["Aug", "13 ", "Thu"] {
+            assert!(text.contains(label), "{label}");
+        }
+        assert!(!text.contains("Jan"));
+        assert!(!text.contains('\u{1b}'));
+        assert_eq!(solution.label(0, 0), Some("JAN"));
+        assert_eq!(solution.label(8, 5), None);
+    }
 
     #[test]
     fn check_new_board() {
@@ -387,7 +493,7 @@ mod test {
     #[test]
     fn check_some_dates() {
         let mut b: Board = Board::new();
-        b.set_date(1, 1, 1);
+        b.set_date(1, 1, 1).unwrap();
         assert_eq!(
             b.table,
             [
@@ -401,7 +507,7 @@ mod test {
         );
 
         b = Board::new();
-        b.set_date(4, 13, 8);
+        b.set_date(4, 13, 8).unwrap();
         assert_eq!(
             b.table,
             [
@@ -415,7 +521,7 @@ mod test {
         );
 
         b = Board::new();
-        b.set_date(7, 14, 12);
+        b.set_date(7, 14, 12).unwrap();
         assert_eq!(
             b.table,
             [
@@ -430,17 +536,92 @@ mod test {
     }
 
     #[test]
-    #[should_panic]
     fn wrong_date() {
         let mut b: Board = Board::new();
-        b.set_date(8, 1, 1);
+        assert_eq!(b.set_date(8, 1, 1), Err(DateError::InvalidWeekDay(8)));
+        assert_eq!(b.set_date(1, 0, 1), Err(DateError::InvalidDay(0)));
+        assert_eq!(b.set_date(1, 32, 1), Err(DateError::InvalidDay(32)));
+        assert_eq!(b.set_date(1, 1, 13), Err(DateError::InvalidMonth(13)));
+        assert_eq!(
+            b.set_date(1, 30, 2),
+            Err(DateError::NoSuchDate { day: 30, month: 2 })
+        );
+        assert_eq!(b, Board::new());
+
+        assert_eq!(b.set_date(1, 1, 1), Ok(()));
+        assert_eq!(
+            b.set_date(2, 1, 1),
+            Err(DateError::AlreadyCovered("1".to_string()))
+        );
+        // a piece over the week day
+        let mut b: Board = Board::new();
+        b.set_cell(7, 0, 'f');
+        assert_eq!(
+            b.set_date(1, 1, 1),
+            Err(DateError::AlreadyCovered("MON".to_string()))
+        );
+    }
+
+    #[test]
+    fn chrono_dates() {
+        let date = NaiveDate::from_ymd_opt(2026, 8, 13).unwrap();
+        let mut b = Board::new();
+        b.set_date(4, 13, 8).unwrap();
+        assert_eq!(Board::for_date(date), b);
+
+        let mut from = Board::new();
+        from.set_date_from(date.and_hms_opt(12, 0, 0).unwrap())
+            .unwrap();
+        assert_eq!(from, b);
+
+        // Feb 30th is on the board, but only as any combination
+        let mut b = Board::new();
+        assert_eq!(
+            Date::new(1, 30, 2).and_then(|date| b.set_date_to(date)),
+            Err(DateError::NoSuchDate { day: 30, month: 2 })
+        );
+        assert_eq!(b, Board::new());
+        b.set_date_to(Date::any_combination(1, 30, 2).unwrap())
+            .unwrap();
+        assert_ne!(b, Board::new());
+    }
+
+    #[test]
+    fn other_layout() {
+        let layout: BoardLayout = "JAN FEB # 1 2 MON\n. . . 3 4 TUE".parse().unwrap();
+        let mut b = Board::from_layout(layout);
+        b.set_date(2, 4, 1).unwrap();
+        assert_eq!((b.width(), b.height()), (6, 2));
+        assert_eq!(
+            b.table,
+            [
+                ['X', '0', 'X', '0', '0', '0'],
+                ['0', '0', '0', '0', 'X', 'X']
+            ]
+        );
+    }
+
+    #[test]
+    fn not_on_the_layout() {
+        let layout: BoardLayout = "JAN FEB # 1 2 MON\n. . . 3 4 TUE".parse().unwrap();
+        let mut b = Board::from_layout(layout);
+        assert_eq!(
+            b.set_date(1, 5, 1),
+            Err(DateError::UnknownLabel("5".to_string()))
+        );
+        assert_eq!(
+            b.set_date(3, 1, 1),
+            Err(DateError::UnknownLabel("WED".to_string()))
+        );
+        // nothing was blocked
+        assert_eq!(b.table[0][3], '0');
     }
 
     #[test]
     fn solvable() {
         let mut b: Board = Board::new();
-        b.set_date(1, 2, 3);
-        assert_eq!(true, b.is_solvable());
+        b.set_date(1, 2, 3).unwrap();
+        assert!(b.is_solvable());
         assert_eq!(
             b.table,
             [
@@ -460,8 +641,10 @@ mod test {
             ['0', '0', 'w', '0', 'f', '0', '0', '0', '0'],
             ['X', '0', 'w', 'f', '0', '0', '0', '0', '0'],
             ['0', '0', 'f', 'f', 'f', '0', '0', '0', 'X'],
-        ];
-        assert_eq!(true, b.is_solvable());
+        ]
+        .map(Vec::from)
+        .into();
+        assert!(b.is_solvable());
         assert_eq!(
             b.table,
             [
@@ -475,37 +658,84 @@ mod test {
         );
     }
 
+    #[test]
+    fn solvable_with_many_areas() {
+        // eleven lines of 5 cells cut off from each other, on a board too large for a u64
+        let mut b = Board::with_size(5, 21).unwrap();
+        for line in (1..21).step_by(2) {
+            for column in 0..5 {
+                b.set_cell(column, line, 'X');
+            }
+        }
+        assert!(b.is_solvable());
+        b.set_cell(0, 20, 'X');
+        assert!(!b.is_solvable());
+
+        // the largest board there can be, a larger one is refused before anything is checked
+        let mut b = Board::with_size(16, 8).unwrap();
+        assert!(!b.is_solvable());
+        b.set_cell(0, 0, 'X');
+        b.set_cell(1, 0, 'X');
+        b.set_cell(2, 0, 'X');
+        assert!(b.is_solvable());
+        assert!(Board::with_size(12, 12).is_err());
+    }
+
+    #[test]
+    fn placed_anywhere() {
+        let mut b: Board = Board::new();
+        b.set_date(1, 1, 1).unwrap();
+        let v = OrientedPiece {
+            pattern: vec![
+                vec!['v', 'v', 'v'],
+                vec!['v', '0', '0'],
+                vec!['v', '0', '0'],
+            ],
+            top_index: 0,
+        };
+        // JAN is part of the date
+        assert!(!b.fits_at(&v, 0, 0));
+        assert!(!b.place_piece_at(&v, 0, 0));
+        // off the board
+        assert!(!b.fits_at(&v, 7, 0));
+        assert!(b.place_piece_at(&v, 1, 1));
+        assert_eq!(b.cell(3, 1), 'v');
+        assert_eq!(b.cell(1, 3), 'v');
+        assert_eq!(b.cell(2, 2), '0');
+        assert!(!b.fits_at(&v, 1, 3));
+    }
+
     #[test]
     fn tricky_placement_offset() {
         let mut b: Board = Board::new();
-        b.set_date(1, 1, 1);
+        b.set_date(1, 1, 1).unwrap();
         b.place_piece_on_top_left(&OrientedPiece {
-            pattern: [
-                ['0', 'f', '0', '0', '0'],
-                ['f', 'f', 'f', '0', '0'],
-                ['0', '0', 'f', '0', '0'],
-                ['0', '0', '0', '0', '0'],
-                ['0', '0', '0', '0', '0'],
+            pattern: vec![
+                vec!['0', 'f', '0', '0', '0'],
+                vec!['f', 'f', 'f', '0', '0'],
+                vec!['0', '0', 'f', '0', '0'],
+                vec!['0', '0', '0', '0', '0'],
+                vec!['0', '0', '0', '0', '0'],
             ],
             top_index: 1,
         });
         b.place_piece_on_top_left(&OrientedPiece {
-            pattern: [
-                ['l', 'l', '0', '0', '0'],
-                ['0', 'l', '0', '0', '0'],
-                ['0', 'l', '0', '0', '0'],
-                ['0', 'l', '0', '0', '0'],
-                ['0', '0', '0', '0', '0'],
+            pattern: vec![
+                vec!['l', 'l', '0', '0', '0'],
+                vec!['0', 'l', '0', '0', '0'],
+                vec!['0', 'l', '0', '0', '0'],
+                vec!['0', 'l', '0', '0', '0'],
+                vec!['0', '0', '0', '0', '0'],
             ],
             top_index: 0,
         });
         b.place_piece_on_top_left(&OrientedPiece {
-            pattern: [
-                ['0', 'q', 'q', '0', '0'],
-                ['q', 'q', 'q', '0', '0'],
-                ['0', '0', '0', '0', '0'],
-                ['0', '0', '0', '0', '0'],
-                ['0', '0', '0', '0', '0'],
+            pattern: vec![
+                vec!['0', 'q', 'q', '0', '0'],
+                vec!['q', 'q', 'q', '0', '0'],
+                vec!['0', '0', '0', '0', '0'],
+                vec!['0', '0', '0', '0', '0'],
+                vec!['0', '0', '0', '0', '0'],
             ],
             top_index: 1,
         });
@@ -517,16 +747,47 @@ mod test {
             ['0', '0', '0', '0', '0', '0', '0', '0', '0'],
             ['0', '0', '0', '0', '0', '0', '0', '0', 'X'],
         ]);
-        assert_eq!(b.place_piece_on_top_left(&OrientedPiece {
-            pattern: [
-                ['0', 'y', '0', '0', '0'],
-                ['y', 'y', '0', '0', '0'],
-                ['0', 'y', '0', '0', '0'],
-                ['0', 'y', '0', '0', '0'],
-                ['0', '0', '0', '0', '0'],
+        assert!(b.place_piece_on_top_left(&OrientedPiece {
+            pattern: vec![
+                vec!['0', 'y', '0', '0', '0'],
+                vec!['y', 'y', '0', '0', '0'],
+                vec!['0', 'y', '0', '0', '0'],
+                vec!['0', 'y', '0', '0', '0'],
+                vec!['0', '0', '0', '0', '0'],
             ],
             top_index: 1,
-        }), true);
+        }));
 
     }
+
+    #[test]
+    fn larger_than_a_pentomino() {
+        let mut b = Board::with_size(7, 2).unwrap();
+        let long = Piece::new('h', &["hhhhhhh"], Rules::OneSided);
+        assert!(b.place_piece_on_top_left(&long.possible_pieces[0]));
+        // standing up, it goes over the bottom edge
+        assert!(!b.place_piece_on_top_left(&long.possible_pieces[1]));
+        assert_eq!(b.table, [vec!['h'; 7], vec!['0'; 7]]);
+        assert!(!b.is_full());
+    }
+
+    #[test]
+    fn no_room_left_of_first_free_space() {
+        let mut b: Board = Board::new();
+        b.table[0] = vec!['X'; b.width()];
+        let before = b.table.clone();
+        // the first free space is on the left edge, there is no room for the 'f' on its left
+        // (it used to go on (1, 1) instead, leaving (0, 1) to another piece)
+        assert!(!b.place_piece_on_top_left(&OrientedPiece {
+            pattern: vec![
+                vec!['0', 'f', '0', '0', '0'],
+                vec!['f', 'f', 'f', '0', '0'],
+                vec!['0', '0', 'f', '0', '0'],
+                vec!['0', '0', '0', '0', '0'],
+                vec!['0', '0', '0', '0', '0'],
+            ],
+            top_index: 1,
+        }));
+        assert_eq!(b.table, before);
+    }
 }
@@ -0,0 +1,486 @@
+use chrono::{Local, NaiveDate};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use calendar_puzzle::piece::{Piece, Rules};
+use calendar_puzzle::progress::Progress;
+use calendar_puzzle::{Backtracking, BoardLayout, Dlx, Parallel, PieceSet, Puzzle, Solver};
+
+/// Find every way to place the pieces on the calendar for a given date
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// How the results are printed
+    #[arg(short, long, value_enum, default_value_t = Format::Boards, global = true)]
+    pub format: Format,
+
+    /// Draw the solutions with a colour for each piece, with their ids instead when the output
+    /// is not a terminal
+    #[arg(long, global = true)]
+    pub color: bool,
+
+    /// Also write the labels under the pieces, faintly, with `--color` on a terminal
+    #[arg(long, global = true, requires = "color")]
+    pub all_labels: bool,
+
+    /// Also draw each solution shown in an SVG file in that directory, named after the date and
+    /// the number of the solution
+    #[arg(long, global = true, value_name = "DIR")]
+    pub svg: Option<PathBuf>,
+
+    /// Stop looking after that many solutions for a date
+    #[arg(short = 'n', long, global = true)]
+    pub limit: Option<usize>,
+
+    /// Do not show anything while searching, only print the results
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// What to show on the terminal while searching
+    #[arg(short, long, value_enum, default_value_t = ShowProgress::Live, global = true)]
+    pub progress: ShowProgress,
+
+    /// How many times per second the board is redrawn with `--progress live`
+    #[arg(long, default_value_t = 10, global = true)]
+    pub fps: u32,
+
+    /// Which calendar to solve, with its board and its pieces
+    #[arg(long, value_enum, default_value_t = Calendar::Weekday, global = true)]
+    pub puzzle: Calendar,
+
+    /// File with the layout of the board, instead of the one of the puzzle
+    #[arg(long, global = true, value_parser = parse_layout)]
+    pub layout: Option<BoardLayout>,
+
+    /// File with the pieces, instead of the ones of the puzzle
+    #[arg(long, global = true, value_parser = parse_pieces)]
+    pub pieces: Option<PieceSet>,
+
+    /// Let the pieces be flipped over as well as rotated, they always can be on a-puzzle-a-day
+    #[arg(long, global = true)]
+    pub free: bool,
+
+    /// Skip the solutions that only swap pieces of the same shape, or that are turned or flipped
+    /// versions of earlier ones on a board that looks the same once turned or flipped
+    #[arg(long, global = true)]
+    pub distinct: bool,
+
+    /// Number of threads searching at the same time, the progress is not shown with more than one
+    #[arg(short = 'j', long, default_value_t = 1, global = true)]
+    pub threads: usize,
+
+    /// How to look for the solutions, only the backtracking uses several threads
+    #[arg(short, long, value_enum, default_value_t = Backend::Backtracking, global = true)]
+    pub backend: Backend,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Solve the puzzle for a date
+    Solve {
+        #[arg(value_parser = parse_date)]
+        date: NaiveDate,
+    },
+    /// Only print how many solutions a date has
+    Count {
+        #[arg(value_parser = parse_date)]
+        date: NaiveDate,
+        /// Also print how many solutions put each piece on each cell, only with the backtracking
+        /// on one thread and without --limit
+        #[arg(long)]
+        histogram: bool,
+    },
+    /// Solve the puzzle for today
+    Today,
+    /// Solve the puzzle for every date between FROM and TO (included)
+    Range {
+        #[arg(value_parser = parse_date)]
+        from: NaiveDate,
+        #[arg(value_parser = parse_date)]
+        to: NaiveDate,
+    },
+    /// Solve every week day, day and month combination the board accepts
+    Survey,
+    /// Place the pieces by hand on the terminal, for today if no date is given
+    Play {
+        #[arg(value_parser = parse_date)]
+        date: Option<NaiveDate>,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Every board followed by the number of solutions
+    Boards,
+    /// One line per date with the number of solutions and the time it took
+    Summary,
+    /// One JSON object per solution and per line, with the date, where each piece is and the
+    /// cells of the board
+    Json,
+    /// The summary as CSV, with a header line first
+    Csv,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Calendar {
+    /// The 9x6 calendar with the week days and ten pentominoes
+    Weekday,
+    /// The 7x7 calendar with only the months and the days, with a 2x3 rectangle among the pieces
+    APuzzleADay,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShowProgress {
+    /// The board as the search goes
+    Live,
+    /// A progress bar with the nodes explored, the solutions found and the time spent
+    Bar,
+    /// Nothing, same as --quiet
+    None,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// Fill the first free space of the board with each piece that fits, one after the other
+    Backtracking,
+    /// Knuth's Algorithm X with dancing links, the puzzle as an exact cover problem
+    Dlx,
+}
+
+impl Backend {
+    pub fn solver(self, threads: usize, progress: Progress) -> Box<dyn Solver> {
+        match self {
+            Backend::Backtracking if threads > 1 => Box::new(Parallel { threads }),
+            Backend::Backtracking => Box::new(Backtracking { progress }),
+            Backend::Dlx => Box::new(Dlx),
+        }
+    }
+}
+
+// dateparser gives back a UTC time, bring it back to the local date that was typed
+pub fn parse_date(input: &str) -> Result<NaiveDate, String> {
+    dateparser::parse(input.trim())
+        .map(|d| d.with_timezone(&Local).date_naive())
+        .map_err(|error| format!("could not parse the date [{}]: {error}", input.trim()))
+}
+
+pub fn parse_layout(path: &str) -> Result<BoardLayout, String> {
+    // the survey and the dates all expect every label to be there
+    BoardLayout::from_file(path)
+        .and_then(|layout| layout.check_dates().map(|()| layout))
+        .map_err(|error| format!("{path}: {error}"))
+}
+
+pub fn parse_pieces(path: &str) -> Result<PieceSet, String> {
+    PieceSet::from_file(path).map_err(|error| format!("{path}: {error}"))
+}
+
+impl Cli {
+    // dates to solve, in order, or None when we should ask for one
+    pub fn dates(&self) -> Option<Vec<NaiveDate>> {
+        match self.command.as_ref()? {
+            // the survey goes through the board combinations, not through dates
+            Command::Survey => None,
+            Command::Solve { date } | Command::Count { date, .. } => Some(vec![*date]),
+            Command::Today | Command::Play { date: None } => Some(vec![Local::now().date_naive()]),
+            Command::Play { date: Some(date) } => Some(vec![*date]),
+            Command::Range { from, to } => {
+                Some(from.iter_days().take_while(|d| d <= to).collect())
+            }
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(usize::MAX)
+    }
+
+    // only the backtracking on a single thread can show its progress, and only to a terminal
+    pub fn progress(&self) -> Progress {
+        if self.quiet
+            || self.threads > 1
+            || self.backend != Backend::Backtracking
+            || !termion::is_tty(&io::stderr())
+        {
+            return Progress::Quiet;
+        }
+        match self.progress {
+            ShowProgress::Live => Progress::Live { fps: self.fps },
+            ShowProgress::Bar => Progress::Bar,
+            ShowProgress::None => Progress::Quiet,
+        }
+    }
+
+    pub fn histogram(&self) -> bool {
+        matches!(
+            self.command,
+            Some(Command::Count {
+                histogram: true,
+                ..
+            })
+        )
+    }
+
+    // the histogram needs to know where each piece went, only a whole backtracking search on a
+    // single thread keeps that
+    pub fn check_histogram(&self) -> Result<(), String> {
+        if !self.histogram() {
+            return Ok(());
+        }
+        if self.backend != Backend::Backtracking || self.threads > 1 {
+            return Err("--histogram only works with the backtracking on one thread".to_string());
+        }
+        if self.limit.is_some() {
+            return Err("--histogram cannot be used with --limit".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn puzzle(&self) -> Puzzle {
+        match self.puzzle {
+            Calendar::Weekday => Puzzle::Weekday,
+            Calendar::APuzzleADay => Puzzle::APuzzleADay,
+        }
+    }
+
+    pub fn layout(&self) -> Arc<BoardLayout> {
+        match &self.layout {
+            Some(layout) => Arc::new(layout.clone()),
+            None => self.puzzle().layout(),
+        }
+    }
+
+    pub fn rules(&self) -> Rules {
+        if self.free {
+            Rules::Free
+        } else {
+            self.puzzle().rules()
+        }
+    }
+
+    pub fn piece_set(&self) -> PieceSet {
+        self.pieces
+            .clone()
+            .unwrap_or_else(|| self.puzzle().piece_set())
+    }
+
+    pub fn pieces(&self) -> Vec<Piece> {
+        self.piece_set().pieces(self.rules())
+    }
+
+    pub fn format(&self) -> Format {
+        match self.command {
+            // count never prints the boards
+            Some(Command::Count { .. }) if self.format == Format::Boards => Format::Summary,
+            _ => self.format,
+        }
+    }
+
+    // --format is global, clap cannot tell which commands can use which formats
+    pub fn check_format(&self) -> Result<(), String> {
+        match (&self.command, self.format) {
+            (Some(Command::Count { .. }), Format::Json) => {
+                Err("count has no solutions to print with --format json".to_string())
+            }
+            (Some(Command::Count { .. }), Format::Csv) if self.histogram() => {
+                Err("the histogram cannot be printed with --format csv".to_string())
+            }
+            (Some(Command::Survey), Format::Json | Format::Csv) => {
+                Err("the survey can only be printed as a summary".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_arguments() {
+        let cli = Cli::parse_from(["calendar-puzzle", "solve", "2024-03-02", "-n", "1", "-q"]);
+        assert_eq!(
+            cli.dates(),
+            Some(vec![NaiveDate::from_ymd_opt(2024, 3, 2).unwrap()])
+        );
+        assert_eq!(cli.limit, Some(1));
+        assert!(cli.quiet);
+        assert_eq!(cli.format(), Format::Boards);
+
+        let cli = Cli::parse_from(["calendar-puzzle", "count", "2024-03-02"]);
+        assert_eq!(cli.format(), Format::Summary);
+
+        let cli = Cli::parse_from(["calendar-puzzle"]);
+        assert_eq!(cli.dates(), None);
+    }
+
+    #[test]
+    fn threads() {
+        assert_eq!(Cli::parse_from(["calendar-puzzle", "today"]).threads, 1);
+        let cli = Cli::parse_from(["calendar-puzzle", "survey", "-j", "8"]);
+        assert_eq!(cli.threads, 8);
+    }
+
+    #[test]
+    fn histogram() {
+        let cli = Cli::parse_from(["calendar-puzzle", "count", "2024-03-02"]);
+        assert!(!cli.histogram());
+
+        let cli = Cli::parse_from(["calendar-puzzle", "count", "2024-03-02", "--histogram"]);
+        assert!(cli.histogram());
+        assert!(cli.check_histogram().is_ok());
+        for other in [["-b", "dlx"], ["-j", "4"], ["-n", "10"]] {
+            let args = ["calendar-puzzle", "count", "2024-03-02", "--histogram"];
+            let cli = Cli::parse_from(args.iter().chain(&other));
+            assert!(cli.check_histogram().is_err());
+        }
+    }
+
+    #[test]
+    fn rules() {
+        assert_eq!(
+            Cli::parse_from(["calendar-puzzle"]).rules(),
+            Rules::OneSided
+        );
+        assert_eq!(
+            Cli::parse_from(["calendar-puzzle", "--free"]).rules(),
+            Rules::Free
+        );
+    }
+
+    #[test]
+    fn distinct() {
+        assert!(!Cli::parse_from(["calendar-puzzle", "today"]).distinct);
+        assert!(Cli::parse_from(["calendar-puzzle", "today", "--distinct"]).distinct);
+    }
+
+    #[test]
+    fn backend() {
+        let cli = Cli::parse_from(["calendar-puzzle", "today"]);
+        assert_eq!(cli.backend, Backend::Backtracking);
+
+        let cli = Cli::parse_from(["calendar-puzzle", "today", "--backend", "dlx"]);
+        assert_eq!(cli.backend, Backend::Dlx);
+    }
+
+    #[test]
+    fn progress() {
+        let cli = Cli::parse_from(["calendar-puzzle", "today"]);
+        assert_eq!(cli.progress, ShowProgress::Live);
+        assert_eq!(cli.fps, 10);
+
+        let cli = Cli::parse_from(["calendar-puzzle", "today", "-p", "bar", "--fps", "5"]);
+        assert_eq!(cli.progress, ShowProgress::Bar);
+        assert_eq!(cli.fps, 5);
+    }
+
+    #[test]
+    fn formats() {
+        let cli = Cli::parse_from(["calendar-puzzle", "count", "2024-03-02", "-f", "csv"]);
+        assert_eq!(cli.format(), Format::Csv);
+        assert!(cli.check_format().is_ok());
+        let cli = Cli::parse_from(["calendar-puzzle", "count", "2024-03-02", "-f", "json"]);
+        assert!(cli.check_format().is_err());
+
+        let cli = Cli::parse_from(["calendar-puzzle", "survey"]);
+        assert!(cli.check_format().is_ok());
+        let cli = Cli::parse_from(["calendar-puzzle", "survey", "-f", "csv"]);
+        assert!(cli.check_format().is_err());
+    }
+
+    #[test]
+    fn svg() {
+        assert_eq!(Cli::parse_from(["calendar-puzzle", "today"]).svg, None);
+        let cli = Cli::parse_from(["calendar-puzzle", "today", "--svg", "pictures"]);
+        assert_eq!(cli.svg, Some(PathBuf::from("pictures")));
+    }
+
+    #[test]
+    fn color() {
+        let cli = Cli::parse_from(["calendar-puzzle", "today"]);
+        assert!(!cli.color);
+        assert!(!cli.all_labels);
+
+        let cli = Cli::parse_from(["calendar-puzzle", "--color", "--all-labels"]);
+        assert!(cli.color);
+        assert!(cli.all_labels);
+        assert!(Cli::try_parse_from(["calendar-puzzle", "--all-labels"]).is_err());
+    }
+
+    #[test]
+    fn play() {
+        let cli = Cli::parse_from(["calendar-puzzle", "play", "2024-03-02"]);
+        assert_eq!(
+            cli.dates(),
+            Some(vec![NaiveDate::from_ymd_opt(2024, 3, 2).unwrap()])
+        );
+        let cli = Cli::parse_from(["calendar-puzzle", "play"]);
+        assert_eq!(cli.dates().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn range_is_inclusive() {
+        let cli = Cli::parse_from(["calendar-puzzle", "range", "2024-02-27", "2024-03-02"]);
+        let dates = cli.dates().unwrap();
+        assert_eq!(dates.len(), 5);
+        assert_eq!(dates[0], NaiveDate::from_ymd_opt(2024, 2, 27).unwrap());
+        assert_eq!(dates[4], NaiveDate::from_ymd_opt(2024, 3, 2).unwrap());
+
+        let cli = Cli::parse_from(["calendar-puzzle", "range", "2024-03-02", "2024-02-27"]);
+        assert!(cli.dates().unwrap().is_empty());
+    }
+
+    #[test]
+    fn bad_date() {
+        assert!(Cli::try_parse_from(["calendar-puzzle", "solve", "not a date"]).is_err());
+    }
+
+    #[test]
+    fn layout_file() {
+        let cli = Cli::parse_from(["calendar-puzzle", "--layout", "layouts/weekday.txt"]);
+        assert_eq!(cli.layout(), BoardLayout::weekday());
+        assert_eq!(
+            Cli::parse_from(["calendar-puzzle"]).layout(),
+            BoardLayout::weekday()
+        );
+        assert!(Cli::try_parse_from(["calendar-puzzle", "--layout", "no/such/file"]).is_err());
+    }
+
+    #[test]
+    fn puzzle() {
+        let cli = Cli::parse_from(["calendar-puzzle"]);
+        assert_eq!(cli.puzzle(), Puzzle::Weekday);
+        assert_eq!(cli.pieces().len(), 10);
+
+        let cli = Cli::parse_from(["calendar-puzzle", "today", "--puzzle", "a-puzzle-a-day"]);
+        assert_eq!(cli.puzzle(), Puzzle::APuzzleADay);
+        assert_eq!(cli.layout(), BoardLayout::a_puzzle_a_day());
+        assert_eq!(cli.rules(), Rules::Free);
+        assert_eq!(cli.pieces().len(), 8);
+
+        // the layout file wins over the one of the puzzle
+        let cli = Cli::parse_from([
+            "calendar-puzzle",
+            "--puzzle",
+            "a-puzzle-a-day",
+            "--layout",
+            "layouts/weekday.txt",
+        ]);
+        assert_eq!(cli.layout(), BoardLayout::weekday());
+        assert!(Cli::try_parse_from(["calendar-puzzle", "--puzzle", "nope"]).is_err());
+    }
+
+    #[test]
+    fn pieces_file() {
+        let cli = Cli::parse_from(["calendar-puzzle", "--pieces", "pieces/a_puzzle_a_day.txt"]);
+        assert_eq!(cli.piece_set(), PieceSet::a_puzzle_a_day());
+        // the pieces of one puzzle do not fit the board of the other
+        assert!(cli.piece_set().check_area(&cli.layout()).is_err());
+        assert!(Cli::try_parse_from(["calendar-puzzle", "--pieces", "no/such/file"]).is_err());
+    }
+}
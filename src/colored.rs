@@ -0,0 +1,178 @@
+use std::fmt;
+use std::io;
+
+use termion::color::{Bg, Reset, Rgb};
+use termion::style::{Faint, NoFaint};
+
+use crate::board::Board;
+use crate::layout::{title_case, Cell};
+use crate::piece;
+
+// a cell is drawn as a block of characters, about as high as it is wide on most terminals
+pub(crate) const CELL_WIDTH: usize = 5;
+pub(crate) const CELL_HEIGHT: usize = 2;
+
+/// Draws a board with a background colour for each piece, its [`piece::color`], and the
+/// labels of the cells no piece covers, the date on a solved board. Without colours, each
+/// piece is drawn with its id instead. [`Colored::with_all_labels`] also writes the labels under
+/// the pieces, faintly.
+///
+/// ```
+/// use calendar_puzzle::colored::Colored;
+/// use calendar_puzzle::Board;
+///
+/// let mut board = Board::new();
+/// board.set_date(1, 1, 1).unwrap();
+/// println!("{}", Colored::new(&board));
+/// ```
+pub struct Colored<'a> {
+    board: &'a Board,
+    colors: bool,
+    all_labels: bool,
+}
+
+impl<'a> Colored<'a> {
+    /// In colour if stdout is a terminal, in plain text otherwise so that it can be redirected
+    /// to a file
+    pub fn new(board: &'a Board) -> Colored<'a> {
+        Colored::with_colors(board, termion::is_tty(&io::stdout()))
+    }
+
+    /// In colour or in plain text whatever stdout is
+    pub fn with_colors(board: &'a Board, colors: bool) -> Colored<'a> {
+        Colored {
+            board,
+            colors,
+            all_labels: false,
+        }
+    }
+
+    /// Whether to write the labels under the pieces as well, only when in colour
+    pub fn with_all_labels(mut self, all_labels: bool) -> Colored<'a> {
+        self.all_labels = all_labels;
+        self
+    }
+}
+
+impl Colored<'_> {
+    // one of the CELL_HEIGHT lines of a cell, CELL_WIDTH characters wide once on the terminal
+    pub(crate) fn cell(&self, column: usize, line: usize, row: usize) -> String {
+        let b = self.board;
+        let label = match b.layout().cell(column, line) {
+            Cell::Blocked => return " ".repeat(CELL_WIDTH),
+            Cell::Free(label) => label,
+        };
+        let id = b.cell(column, line);
+        if id != 'X' && id != '0' {
+            if !self.colors {
+                return id.to_string().repeat(CELL_WIDTH);
+            }
+            let (red, green, blue) = piece::color(id);
+            let fill = match label {
+                Some(label) if row == 0 && self.all_labels => {
+                    let label = title_case(label);
+                    format!("{Faint}{label:^CELL_WIDTH$.CELL_WIDTH$}{NoFaint}")
+                }
+                _ => " ".repeat(CELL_WIDTH),
+            };
+            return format!("{}{fill}{}", Bg(Rgb(red, green, blue)), Bg(Reset));
+        }
+        // the label goes on the first line of the cell, a free cell without one gets a dot so
+        // that it can be told from the outside of the board
+        let text = match (row, label) {
+            (0, Some(label)) => title_case(label),
+            (0, None) if id == '0' => ".".to_string(),
+            _ => String::new(),
+        };
+        format!("{text:^CELL_WIDTH$.CELL_WIDTH$}")
+    }
+}
+
+impl fmt::Display for Colored<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        for line in 0..self.board.height() {
+            for row in 0..CELL_HEIGHT {
+                for column in 0..self.board.width() {
+                    write!(f, "{}", self.cell(column, line, row))?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::layout::BoardLayout;
+    use crate::piece::all_pieces;
+    use crate::solver::{Backtracking, Solver};
+
+    #[test]
+    fn plain() {
+        let mut b = Board::new();
+        b.set_date(4, 13, 8).unwrap();
+        let solution = Backtracking::default()
+            .solve_first(&b, &all_pieces())
+            .unwrap();
+        let text = Colored::with_colors(&solution, false).to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 12);
+        // Aug is the first cell of the fifth line, 13 and Thu are on the third one
+        assert_eq!(&lines[8][..5], " Aug ");
+        assert_eq!(&lines[9][..5], "     ");
+        assert_eq!(&lines[4][20..25], " 13  ");
+        assert_eq!(&lines[4][35..40], " Thu ");
+        assert!(lines.iter().all(|line| line.len() == 9 * CELL_WIDTH));
+        // no free cell is left
+        assert!(!text.contains('.'));
+        assert!(!text.contains('\u{1b}'));
+
+        // the empty board shows every label, and the cells without one
+        let text = Colored::with_colors(&Board::with_size(2, 1).unwrap(), false).to_string();
+        assert_eq!(text, "  .    .  \n          \n");
+    }
+
+    #[test]
+    fn colors() {
+        let mut b = Board::new();
+        b.set_date(1, 1, 1).unwrap();
+        let solution = Backtracking::default()
+            .solve_first(&b, &all_pieces())
+            .unwrap();
+        let text = Colored::with_colors(&solution, true).to_string();
+        let (red, green, blue) = piece::color('t');
+        assert!(text.contains(&Bg(Rgb(red, green, blue)).to_string()));
+        assert!(text.contains(" Jan "));
+        assert!(text.contains(" Mon "));
+        assert!(!text.contains('t'));
+        assert!(!text.contains(" Feb "));
+
+        // the labels under the pieces can be seen as well
+        let text = Colored::with_colors(&solution, true)
+            .with_all_labels(true)
+            .to_string();
+        assert!(text.contains(&format!("{Faint} Feb {NoFaint}")));
+        let plain = Colored::with_colors(&solution, false).with_all_labels(true);
+        assert!(!plain.to_string().contains("Feb"));
+        assert_eq!(title_case("SUN"), "Sun");
+        assert_eq!(title_case("31"), "31");
+    }
+
+    #[test]
+    fn long_labels() {
+        // the labels are cut to the width of a cell, as on the plain board
+        let layout: BoardLayout = "SEPTEMBER 1".parse().unwrap();
+        let mut b = Board::from_layout(layout);
+        let text = Colored::with_colors(&b, false).to_string();
+        assert_eq!(text, "Septe  1  \n          \n");
+
+        b.set_cell(0, 0, 'a');
+        let text = Colored::with_colors(&b, true)
+            .with_all_labels(true)
+            .to_string();
+        assert!(text.contains(&format!("{Faint}Septe{NoFaint}")));
+        assert!(!text.contains("Septem"));
+    }
+}
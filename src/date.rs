@@ -0,0 +1,131 @@
+use std::fmt;
+
+use chrono::{Datelike, Month, NaiveDate, Weekday};
+
+use crate::board::DateError;
+
+/// A week day, a day and a month, checked to be on a calendar.
+///
+/// [`Date::new`] only takes dates that exist, [`Date::any_combination`] also takes the ones
+/// that are on the board but never on a calendar, like Feb 30th.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Date {
+    week_day: u8,
+    day: u8,
+    month: u8,
+}
+
+impl Date {
+    /// A date that exists in some year, week_day is 1 for Monday, 7 for Sunday. Feb 29th is
+    /// taken as it exists in leap years.
+    pub fn new(week_day: u8, day: u8, month: u8) -> Result<Date, DateError> {
+        let date = Date::any_combination(week_day, day, month)?;
+        // 2024 is a leap year
+        if NaiveDate::from_ymd_opt(2024, month.into(), day.into()).is_none() {
+            return Err(DateError::NoSuchDate { day, month });
+        }
+        Ok(date)
+    }
+
+    /// Any week day, day and month the board accepts, whether or not it is a real date
+    pub fn any_combination(week_day: u8, day: u8, month: u8) -> Result<Date, DateError> {
+        if !(1..=7).contains(&week_day) {
+            return Err(DateError::InvalidWeekDay(week_day));
+        }
+        if !(1..=31).contains(&day) {
+            return Err(DateError::InvalidDay(day));
+        }
+        if !(1..=12).contains(&month) {
+            return Err(DateError::InvalidMonth(month));
+        }
+        Ok(Date {
+            week_day,
+            day,
+            month,
+        })
+    }
+
+    /// The date of anything chrono can tell the day of
+    pub fn from_datelike(date: &impl Datelike) -> Date {
+        Date {
+            week_day: date.weekday().number_from_monday() as u8,
+            day: date.day() as u8,
+            month: date.month() as u8,
+        }
+    }
+
+    /// 1 for Monday, 7 for Sunday
+    pub fn week_day(&self) -> u8 {
+        self.week_day
+    }
+
+    /// Day of the month
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// 1 for January, 12 for December
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Whether the date exists, see [`Date::new`]
+    pub fn is_real(&self) -> bool {
+        Date::new(self.week_day, self.day, self.month).is_ok()
+    }
+}
+
+impl From<NaiveDate> for Date {
+    fn from(date: NaiveDate) -> Date {
+        Date::from_datelike(&date)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let week_day = Weekday::try_from(self.week_day - 1).expect("week days go from 1 to 7");
+        let month = Month::try_from(self.month).expect("months go from 1 to 12");
+        write!(f, "{} {} {}", week_day, self.day, &month.name()[..3])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn real_dates() {
+        assert!(Date::new(4, 29, 2).is_ok());
+        assert_eq!(
+            Date::new(1, 30, 2),
+            Err(DateError::NoSuchDate { day: 30, month: 2 })
+        );
+        assert_eq!(
+            Date::new(1, 31, 4),
+            Err(DateError::NoSuchDate { day: 31, month: 4 })
+        );
+        assert_eq!(Date::new(0, 1, 1), Err(DateError::InvalidWeekDay(0)));
+        assert_eq!(Date::new(1, 32, 1), Err(DateError::InvalidDay(32)));
+        assert_eq!(Date::new(1, 1, 13), Err(DateError::InvalidMonth(13)));
+
+        let any = Date::any_combination(1, 30, 2).unwrap();
+        assert!(!any.is_real());
+        assert_eq!(any.to_string(), "Mon 30 Feb");
+        assert_eq!(
+            Date::any_combination(1, 1, 0),
+            Err(DateError::InvalidMonth(0))
+        );
+    }
+
+    #[test]
+    fn chrono_dates() {
+        let date = Date::from(NaiveDate::from_ymd_opt(2024, 8, 13).unwrap());
+        assert_eq!((date.week_day(), date.day(), date.month()), (2, 13, 8));
+        assert!(date.is_real());
+        let time = NaiveDate::from_ymd_opt(2025, 12, 31)
+            .unwrap()
+            .and_hms_opt(23, 59, 0)
+            .unwrap();
+        assert_eq!(Date::from_datelike(&time).to_string(), "Wed 31 Dec");
+    }
+}
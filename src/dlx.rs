@@ -0,0 +1,318 @@
+//! Knuth's Algorithm X with dancing links (<https://arxiv.org/abs/cs/0011047>)
+//!
+//! Every way of putting a piece, in one of its orientations, anywhere it fits on the board is a
+//! row of the matrix. There is one column per free cell of the board and one per piece: a
+//! solution is a set of rows covering each column exactly once.
+
+use crate::board;
+use crate::piece;
+use crate::solver::Solver;
+
+const ROOT: usize = 0;
+
+// a row of the matrix
+struct Placement {
+    // index of the piece in the pieces given to the solver
+    piece: usize,
+    id: char,
+    cells: Vec<(usize, usize)>,
+}
+
+// the nodes of the matrix, each one linked to its 4 neighbours. The root is node 0, followed by
+// the column headers and then the 1s of the matrix row after row.
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    row: Vec<usize>,
+    // only meaningful for the column headers: how many nodes are left in that column
+    size: Vec<usize>,
+}
+
+impl Links {
+    fn new(num_columns: usize) -> Links {
+        let headers = num_columns + 1;
+        Links {
+            left: (0..headers).map(|i| (i + headers - 1) % headers).collect(),
+            right: (0..headers).map(|i| (i + 1) % headers).collect(),
+            up: (0..headers).collect(),
+            down: (0..headers).collect(),
+            column: (0..headers).collect(),
+            row: vec![usize::MAX; headers],
+            size: vec![0; headers],
+        }
+    }
+
+    // columns go from 1 to num_columns
+    fn add_row(&mut self, row: usize, columns: &[usize]) {
+        let first = self.left.len();
+        for (i, &c) in columns.iter().enumerate() {
+            let node = first + i;
+            self.left.push(if i == 0 {
+                first + columns.len() - 1
+            } else {
+                node - 1
+            });
+            self.right.push(if i == columns.len() - 1 {
+                first
+            } else {
+                node + 1
+            });
+            self.up.push(self.up[c]);
+            self.down.push(c);
+            self.column.push(c);
+            self.row.push(row);
+            self.size.push(0);
+
+            let above = self.up[c];
+            self.down[above] = node;
+            self.up[c] = node;
+            self.size[c] += 1;
+        }
+    }
+
+    fn cover(&mut self, c: usize) {
+        self.right[self.left[c]] = self.right[c];
+        self.left[self.right[c]] = self.left[c];
+        let mut i = self.down[c];
+        while i != c {
+            let mut j = self.right[i];
+            while j != i {
+                self.down[self.up[j]] = self.down[j];
+                self.up[self.down[j]] = self.up[j];
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, c: usize) {
+        let mut i = self.up[c];
+        while i != c {
+            let mut j = self.left[i];
+            while j != i {
+                self.size[self.column[j]] += 1;
+                self.down[self.up[j]] = j;
+                self.up[self.down[j]] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        self.right[self.left[c]] = c;
+        self.left[self.right[c]] = c;
+    }
+
+    // returns false once on_solution asks to stop
+    fn search(
+        &mut self,
+        chosen: &mut Vec<usize>,
+        on_solution: &mut impl FnMut(&[usize]) -> bool,
+    ) -> bool {
+        if self.right[ROOT] == ROOT {
+            return on_solution(chosen);
+        }
+
+        // the column with the fewest rows left gives the smallest tree
+        let mut c = self.right[ROOT];
+        let mut j = self.right[c];
+        while j != ROOT {
+            if self.size[j] < self.size[c] {
+                c = j;
+            }
+            j = self.right[j];
+        }
+        if self.size[c] == 0 {
+            return true;
+        }
+
+        self.cover(c);
+        let mut r = self.down[c];
+        while r != c {
+            chosen.push(self.row[r]);
+            let mut j = self.right[r];
+            while j != r {
+                self.cover(self.column[j]);
+                j = self.right[j];
+            }
+
+            let keep_going = self.search(chosen, on_solution);
+
+            let mut j = self.left[r];
+            while j != r {
+                self.uncover(self.column[j]);
+                j = self.left[j];
+            }
+            chosen.pop();
+            if !keep_going {
+                self.uncover(c);
+                return false;
+            }
+            r = self.down[r];
+        }
+        self.uncover(c);
+        true
+    }
+}
+
+// every position of every orientation of the pieces that only covers free cells
+fn placements(b: &board::Board, pieces: &[piece::Piece]) -> Vec<Placement> {
+    let mut result = Vec::new();
+    for (piece_index, piece) in pieces.iter().enumerate() {
+        for oriented_piece in &piece.possible_pieces {
+            let squares: Vec<(usize, usize)> = oriented_piece
+                .pattern
+                .iter()
+                .enumerate()
+                .flat_map(|(j, row)| {
+                    row.iter()
+                        .enumerate()
+                        .filter(|(_, &square)| square != '0')
+                        .map(move |(i, _)| (i, j))
+                })
+                .collect();
+
+            for line in 0..b.height() {
+                for column in 0..b.width() {
+                    let cells: Vec<(usize, usize)> = squares
+                        .iter()
+                        .map(|&(i, j)| (column + i, line + j))
+                        .collect();
+                    if cells
+                        .iter()
+                        .all(|&(x, y)| x < b.width() && y < b.height() && b.cell(x, y) == '0')
+                    {
+                        result.push(Placement {
+                            piece: piece_index,
+                            id: piece.id,
+                            cells,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Solves the puzzle as an exact cover problem with dancing links
+pub struct Dlx;
+
+// the matrix of the puzzle, with the placement behind each row
+fn links(b: &board::Board, pieces: &[piece::Piece]) -> (Links, Vec<Placement>) {
+    // cells first, then pieces
+    let mut cell_columns = vec![vec![0; b.width()]; b.height()];
+    let mut num_columns = 0;
+    for (line, row) in cell_columns.iter_mut().enumerate() {
+        for (column, cell_column) in row.iter_mut().enumerate() {
+            if b.cell(column, line) == '0' {
+                num_columns += 1;
+                *cell_column = num_columns;
+            }
+        }
+    }
+
+    let placements = placements(b, pieces);
+    let mut links = Links::new(num_columns + pieces.len());
+    for (row, placement) in placements.iter().enumerate() {
+        let mut columns: Vec<usize> = placement
+            .cells
+            .iter()
+            .map(|&(x, y)| cell_columns[y][x])
+            .collect();
+        columns.push(num_columns + 1 + placement.piece);
+        links.add_row(row, &columns);
+    }
+    (links, placements)
+}
+
+impl Solver for Dlx {
+    fn for_each_solution(
+        &self,
+        b: &board::Board,
+        pieces: &[piece::Piece],
+        on_solution: &mut dyn FnMut(&board::Board) -> bool,
+    ) -> bool {
+        let (mut links, placements) = links(b, pieces);
+        links.search(&mut Vec::new(), &mut |rows| {
+            let mut solution = b.clone();
+            for &row in rows {
+                for &(x, y) in &placements[row].cells {
+                    solution.set_cell(x, y, placements[row].id);
+                }
+            }
+            on_solution(&solution)
+        })
+    }
+
+    fn count(&self, b: &board::Board, pieces: &[piece::Piece]) -> usize {
+        let (mut links, _) = links(b, pieces);
+        let mut count = 0;
+        links.search(&mut Vec::new(), &mut |_| {
+            count += 1;
+            true
+        });
+        count
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::piece_set::PieceSet;
+    use crate::solver::Backtracking;
+
+    fn sorted(solutions: &[board::Board]) -> Vec<String> {
+        let mut result: Vec<String> = solutions.iter().map(|s| format!("{s:?}")).collect();
+        result.sort();
+        result
+    }
+
+    #[test]
+    fn same_solutions_as_backtracking() {
+        // the last one cannot be solved
+        for (week_day, day, month) in [(1, 1, 1), (6, 2, 3), (7, 14, 7), (4, 13, 8), (1, 5, 2)] {
+            let mut board = board::Board::new();
+            board.set_date(week_day, day, month).unwrap();
+            let pieces = piece::all_pieces();
+
+            let expected = Backtracking::default().solve_up_to(&board, &pieces, usize::MAX);
+            let found = Dlx.solve_up_to(&board, &pieces, usize::MAX);
+
+            assert_eq!(found.len(), expected.len());
+            assert_eq!(Dlx.count(&board, &pieces), expected.len());
+            assert_eq!(sorted(&found), sorted(&expected));
+        }
+    }
+
+    #[test]
+    fn limit() {
+        let mut board = board::Board::new();
+        board.set_date(1, 1, 1).unwrap();
+        let found = Dlx.solve_up_to(&board, &piece::all_pieces(), 3);
+        assert_eq!(found.len(), 3);
+        assert!(found.iter().all(|s| s.is_full()));
+    }
+
+    #[test]
+    fn free_pieces() {
+        let mut board = board::Board::new();
+        board.set_date(6, 2, 3).unwrap();
+        let pieces = piece::all_pieces_with(piece::Rules::Free);
+        assert_eq!(Dlx.count(&board, &pieces), 172);
+        assert_eq!(Backtracking::default().count(&board, &pieces), 172);
+    }
+
+    #[test]
+    fn pentomino_rectangle() {
+        // the twelve pentominoes fill a 3x20 rectangle in only 2 ways, each of them turned 4 ways
+        let pentominoes: PieceSet = include_str!("../pieces/pentominoes.txt").parse().unwrap();
+        let board = board::Board::with_size(20, 3).unwrap();
+        assert_eq!(
+            Dlx.count(&board, &pentominoes.pieces(piece::Rules::Free)),
+            8
+        );
+    }
+}
@@ -0,0 +1,355 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
+use std::{fs, io};
+
+use crate::bitboard::MAX_CELLS;
+
+/// Labels of the week days, from Monday
+pub const WEEK_DAYS: [&str; 7] = ["MON", "TUE", "WED", "THU", "FRI", "SAT", "SUN"];
+/// Labels of the months, from January
+pub const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// What a cell of a [`BoardLayout`] is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cell {
+    /// Outside of the calendar, no piece goes there
+    Blocked,
+    /// A cell the pieces have to cover, with what is written on it if anything
+    Free(Option<String>),
+}
+
+/// Where the months, days and week days are on a calendar.
+///
+/// A layout is written line by line, with the cells separated by spaces: `#` for a cell that
+/// is not part of the calendar, `.` for a cell without anything written on it, and otherwise
+/// the label of the cell, `JAN` to `DEC`, `1` to `31` and `MON` to `SUN`:
+///
+/// ```text
+/// JAN FEB MAR APR 1   2   3   MON TUE
+/// MAY 4   5   6   7   8   9   WED .
+/// ...
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoardLayout {
+    cells: Vec<Vec<Cell>>,
+}
+
+/// Why a layout could not be read
+#[derive(Debug)]
+pub enum LayoutError {
+    /// The file could not be read
+    Io(io::Error),
+    /// There is no cell at all
+    Empty,
+    /// A line does not have as many cells as the first one
+    Ragged {
+        /// Line of the layout, from 1
+        line: usize,
+    },
+    /// The layout has more than [`MAX_CELLS`] (128) cells, the most a bitboard can hold
+    TooLarge {
+        /// Number of columns of the layout
+        columns: usize,
+        /// Number of lines of the layout
+        lines: usize,
+    },
+    /// The same label is on two cells
+    DuplicateLabel(String),
+    /// A month, day or week day is on no cell
+    MissingLabel(String),
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            LayoutError::Io(error) => write!(f, "could not read the layout: {error}"),
+            LayoutError::Empty => write!(f, "the layout has no cells"),
+            LayoutError::Ragged { line } => {
+                write!(
+                    f,
+                    "line {line} does not have as many cells as the first one"
+                )
+            }
+            LayoutError::TooLarge { columns, lines } => write!(
+                f,
+                "the layout is {columns}x{lines}, boards are capped at {MAX_CELLS} cells"
+            ),
+            LayoutError::DuplicateLabel(label) => write!(f, "{label} is on more than one cell"),
+            LayoutError::MissingLabel(label) => write!(f, "{label} is not on the layout"),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+impl From<io::Error> for LayoutError {
+    fn from(error: io::Error) -> LayoutError {
+        LayoutError::Io(error)
+    }
+}
+
+impl BoardLayout {
+    /// The calendar with the week days the puzzle comes with
+    pub fn weekday() -> Arc<BoardLayout> {
+        // every Board::new asks for it, only read it once and share it
+        static WEEKDAY: OnceLock<Arc<BoardLayout>> = OnceLock::new();
+        WEEKDAY
+            .get_or_init(|| {
+                let layout = include_str!("../layouts/weekday.txt").parse();
+                Arc::new(layout.expect("the built-in layout should be valid"))
+            })
+            .clone()
+    }
+
+    /// The 7x7 calendar with only the months and the days, sold as A-Puzzle-A-Day
+    pub fn a_puzzle_a_day() -> Arc<BoardLayout> {
+        static A_PUZZLE_A_DAY: OnceLock<Arc<BoardLayout>> = OnceLock::new();
+        A_PUZZLE_A_DAY
+            .get_or_init(|| {
+                let layout = include_str!("../layouts/a_puzzle_a_day.txt").parse();
+                Arc::new(layout.expect("the built-in layout should be valid"))
+            })
+            .clone()
+    }
+
+    /// A rectangle of free cells without any label, for puzzles that are not calendars. It has
+    /// between 1 and [`MAX_CELLS`] cells, like the layouts that are read.
+    pub fn rectangle(width: usize, height: usize) -> Result<BoardLayout, LayoutError> {
+        if width == 0 || height == 0 {
+            return Err(LayoutError::Empty);
+        }
+        if width.saturating_mul(height) > MAX_CELLS {
+            return Err(LayoutError::TooLarge {
+                columns: width,
+                lines: height,
+            });
+        }
+        Ok(BoardLayout {
+            cells: vec![vec![Cell::Free(None); width]; height],
+        })
+    }
+
+    /// Reads a layout from a file, see [`BoardLayout`] for the format
+    pub fn from_file(path: impl AsRef<Path>) -> Result<BoardLayout, LayoutError> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Number of columns
+    pub fn width(&self) -> usize {
+        self.cells[0].len()
+    }
+
+    /// Number of lines
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// The cell at that place, cells outside of the layout are blocked
+    pub fn cell(&self, column: usize, line: usize) -> &Cell {
+        self.cells
+            .get(line)
+            .and_then(|row| row.get(column))
+            .unwrap_or(&Cell::Blocked)
+    }
+
+    /// What is written on that cell, the opposite of [`BoardLayout::find`]
+    pub fn label(&self, column: usize, line: usize) -> Option<&str> {
+        match self.cell(column, line) {
+            Cell::Free(Some(label)) => Some(label),
+            _ => None,
+        }
+    }
+
+    /// Whether the week days are on the calendar, some only have the months and the days
+    pub fn has_week_days(&self) -> bool {
+        WEEK_DAYS.iter().any(|label| self.find(label).is_some())
+    }
+
+    /// Where the cell with that label is, as (column, line)
+    pub fn find(&self, label: &str) -> Option<(usize, usize)> {
+        self.cells.iter().enumerate().find_map(|(line, row)| {
+            row.iter()
+                .position(|cell| matches!(cell, Cell::Free(Some(l)) if l == label))
+                .map(|column| (column, line))
+        })
+    }
+
+    /// Checks that every month, day and week day has a cell, so that any date can be set. The
+    /// week days can all be left out.
+    pub fn check_dates(&self) -> Result<(), LayoutError> {
+        let days = (1..=31).map(|day: u8| day.to_string());
+        let mut labels: Vec<String> = MONTHS.into_iter().map(String::from).chain(days).collect();
+        if self.has_week_days() {
+            labels.extend(WEEK_DAYS.into_iter().map(String::from));
+        }
+        match labels.into_iter().find(|label| self.find(label).is_none()) {
+            Some(label) => Err(LayoutError::MissingLabel(label)),
+            None => Ok(()),
+        }
+    }
+}
+
+// "JAN" is written "Jan" on the drawings, the days stay as they are
+pub(crate) fn title_case(label: &str) -> String {
+    let mut chars = label.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+impl Default for BoardLayout {
+    fn default() -> BoardLayout {
+        BoardLayout::weekday().as_ref().clone()
+    }
+}
+
+impl FromStr for BoardLayout {
+    type Err = LayoutError;
+
+    fn from_str(text: &str) -> Result<BoardLayout, LayoutError> {
+        let mut cells: Vec<Vec<Cell>> = Vec::new();
+        for (index, text_line) in text.lines().enumerate() {
+            if text_line.trim().is_empty() {
+                continue;
+            }
+            let row: Vec<Cell> = text_line
+                .split_whitespace()
+                .map(|token| match token {
+                    "#" => Cell::Blocked,
+                    "." => Cell::Free(None),
+                    label => Cell::Free(Some(label.to_string())),
+                })
+                .collect();
+            if cells.first().is_some_and(|first| first.len() != row.len()) {
+                return Err(LayoutError::Ragged { line: index + 1 });
+            }
+            cells.push(row);
+        }
+
+        let layout = BoardLayout { cells };
+        if layout.cells.is_empty() {
+            return Err(LayoutError::Empty);
+        }
+        if layout.width() * layout.height() > MAX_CELLS {
+            return Err(LayoutError::TooLarge {
+                columns: layout.width(),
+                lines: layout.height(),
+            });
+        }
+
+        let mut labels: Vec<&str> = Vec::new();
+        for cell in layout.cells.iter().flatten() {
+            if let Cell::Free(Some(label)) = cell {
+                if labels.contains(&label.as_str()) {
+                    return Err(LayoutError::DuplicateLabel(label.clone()));
+                }
+                labels.push(label);
+            }
+        }
+
+        Ok(layout)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn weekday() {
+        let layout = BoardLayout::weekday();
+        assert_eq!((layout.width(), layout.height()), (9, 6));
+        assert_eq!(layout.find("JAN"), Some((0, 0)));
+        assert_eq!(layout.find("14"), Some((7, 5)));
+        assert_eq!(layout.find("SUN"), Some((8, 4)));
+        assert_eq!(layout.find("32"), None);
+        assert_eq!(layout.cell(8, 1), &Cell::Free(None));
+        assert_eq!(layout.cell(8, 5), &Cell::Blocked);
+        assert_eq!(layout.cell(20, 0), &Cell::Blocked);
+        assert!(layout.check_dates().is_ok());
+        assert!(layout.has_week_days());
+        assert!(Arc::ptr_eq(&layout, &BoardLayout::weekday()));
+    }
+
+    #[test]
+    fn a_puzzle_a_day() {
+        let layout = BoardLayout::a_puzzle_a_day();
+        assert_eq!((layout.width(), layout.height()), (7, 7));
+        assert_eq!(layout.find("JUN"), Some((5, 0)));
+        assert_eq!(layout.find("DEC"), Some((5, 1)));
+        assert_eq!(layout.find("1"), Some((0, 2)));
+        assert_eq!(layout.find("31"), Some((2, 6)));
+        assert_eq!(layout.cell(6, 0), &Cell::Blocked);
+        assert_eq!(layout.cell(3, 6), &Cell::Blocked);
+        assert!(!layout.has_week_days());
+        assert!(layout.check_dates().is_ok());
+    }
+
+    #[test]
+    fn bad_layouts() {
+        assert!(matches!("".parse::<BoardLayout>(), Err(LayoutError::Empty)));
+        assert!(matches!(
+            "A B\n\nC".parse::<BoardLayout>(),
+            Err(LayoutError::Ragged { line: 3 })
+        ));
+        assert!(matches!(
+            "A . A".parse::<BoardLayout>(),
+            Err(LayoutError::DuplicateLabel(label)) if label == "A"
+        ));
+        assert!(matches!(
+            ". . . . . . . . . . . . .\n"
+                .repeat(10)
+                .parse::<BoardLayout>(),
+            Err(LayoutError::TooLarge {
+                columns: 13,
+                lines: 10
+            })
+        ));
+        // more than a u64, but not more than a u128
+        assert!(". . . . . . . . . . . . .\n"
+            .repeat(5)
+            .parse::<BoardLayout>()
+            .is_ok());
+        assert!(matches!(
+            BoardLayout::from_file("no/such/layout.txt"),
+            Err(LayoutError::Io(_))
+        ));
+
+        // the same goes for the rectangles
+        assert!(matches!(
+            BoardLayout::rectangle(0, 3),
+            Err(LayoutError::Empty)
+        ));
+        assert!(matches!(
+            BoardLayout::rectangle(12, 12),
+            Err(LayoutError::TooLarge {
+                columns: 12,
+                lines: 12
+            })
+        ));
+        assert!(BoardLayout::rectangle(16, 8).is_ok());
+    }
+
+    #[test]
+    fn missing_dates() {
+        let layout: BoardLayout = "JAN 1 MON".parse().unwrap();
+        assert!(matches!(
+            layout.check_dates(),
+            Err(LayoutError::MissingLabel(label)) if label == "FEB"
+        ));
+        // a single week day asks for all of them
+        let layout = include_str!("../layouts/a_puzzle_a_day.txt").replacen('#', "MON", 1);
+        assert!(matches!(
+            layout.parse::<BoardLayout>().unwrap().check_dates(),
+            Err(LayoutError::MissingLabel(label)) if label == "TUE"
+        ));
+    }
+}
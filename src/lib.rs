@@ -0,0 +1,59 @@
+//! Solver for the calendar puzzle where pentominoes have to cover every cell of the board but
+//! the month, the day and the week day of a date.
+//!
+//! ```
+//! use calendar_puzzle::{all_pieces, Backtracking, Board, Solver};
+//!
+//! let mut board = Board::new();
+//! // Monday 1st of January
+//! board.set_date(1, 1, 1).unwrap();
+//! let solutions = Backtracking::default().solve_all(&board, &all_pieces());
+//! assert_eq!(solutions.len(), 15);
+//! ```
+
+#![warn(missing_docs)]
+
+/// The board stored as bits, used by the backtracking
+pub mod bitboard;
+/// The calendar the pieces go on
+pub mod board;
+/// Drawing boards on a terminal with a colour for each piece
+pub mod colored;
+/// Dates checked to be on a calendar
+pub mod date;
+pub mod dlx;
+/// Where the months, days and week days are on the board
+pub mod layout;
+/// Solutions and counts written for other programs, as JSON or CSV
+pub mod output;
+/// Backtracking on several threads
+pub mod parallel;
+/// Showing how a search is going
+pub mod progress;
+/// The pieces of the puzzle and the ways they can be turned
+pub mod piece;
+/// Sets of pieces read from text files
+pub mod piece_set;
+/// Solving a board by hand on the terminal
+pub mod play;
+/// The calendars the solver knows about
+pub mod puzzle;
+/// The [`Solver`] trait and the backtracking
+pub mod solver;
+/// Drawing boards as SVG pictures
+pub mod svg;
+/// Telling apart the solutions that are really different
+pub mod symmetry;
+/// Solving every date the board accepts
+pub mod survey;
+
+pub use board::{Board, DateError};
+pub use date::Date;
+pub use dlx::Dlx;
+pub use layout::BoardLayout;
+pub use parallel::Parallel;
+pub use piece::{all_pieces, all_pieces_with, get_piece, OrientedPiece, Piece, Rules};
+pub use piece_set::PieceSet;
+pub use puzzle::Puzzle;
+pub use solver::{solutions_for, Backtracking, Histogram, Solver};
+pub use symmetry::{Canonical, Distinct};
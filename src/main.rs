@@ -1,102 +1,344 @@
-use chrono::Datelike;
-use dateparser::parse;
-use std::io;
+use chrono::{Datelike, NaiveDate};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use std::cell::RefCell;
+use std::io::{self, Write};
 use std::time::Instant;
-use termion;
+use std::{fs, process};
 
-mod board;
-mod piece;
+use calendar_puzzle::colored::Colored;
+use calendar_puzzle::progress::Progress;
+use calendar_puzzle::{
+    board, output, play, survey, svg, Backtracking, Distinct, Histogram, Piece, Solver,
+};
+
+mod cli;
+
+use cli::{Cli, Command, Format};
 
 fn main() {
-    let mut board = board::Board::new();
-    let mut pieces = piece::all_pieces();
-    let mut solutions: Vec<board::Board> = Vec::new();
+    let cli = Cli::parse();
+    if let Err(error) = cli.piece_set().check_area(&cli.layout()) {
+        Cli::command()
+            .error(ErrorKind::ValueValidation, error)
+            .exit();
+    }
+
+    if let Err(error) = cli.check_format().and_then(|()| cli.check_histogram()) {
+        Cli::command()
+            .error(ErrorKind::ArgumentConflict, error)
+            .exit();
+    }
+
+    if let Some(dir) = &cli.svg {
+        if let Err(error) = fs::create_dir_all(dir) {
+            Cli::command()
+                .error(ErrorKind::Io, format!("{}: {error}", dir.display()))
+                .exit();
+        }
+    }
+
+    if let Err(error) = run(&mut io::stdout().lock(), &cli) {
+        // the reader went away, as with `| head`, there is nobody left to tell
+        if error.kind() != io::ErrorKind::BrokenPipe {
+            eprintln!("Could not write the results: {error}");
+            process::exit(1);
+        }
+    }
+}
+
+fn run(out: &mut impl Write, cli: &Cli) -> io::Result<()> {
+    if let Some(Command::Survey) = cli.command {
+        return run_survey(out, cli);
+    }
+    if let Some(Command::Play { .. }) = cli.command {
+        return run_play(out, cli);
+    }
+
+    let dates = match cli.dates() {
+        Some(dates) => dates,
+        None => match get_date(out)? {
+            Some(date) => vec![date],
+            // nothing more to read
+            None => return Ok(()),
+        },
+    };
+
+    if cli.format() == Format::Csv {
+        writeln!(out, "{}", output::csv_header(cli.distinct))?;
+    }
+    for date in dates {
+        solve_date(out, cli, date)?;
+    }
+    Ok(())
+}
 
-    let (week_day, day, month) = get_date();
+fn solve_date(out: &mut impl Write, cli: &Cli, date: NaiveDate) -> io::Result<()> {
+    let mut board = board::Board::from_layout(cli.layout());
+    let progress = cli.progress();
 
-    board.set_date(week_day, day, month);
+    if let Err(error) = board.set_date_from(date) {
+        eprintln!("{date}: {error}");
+        return Ok(());
+    }
 
     let now = Instant::now();
+    let solver = cli.backend.solver(cli.threads, progress);
+    let pieces = cli.pieces();
+
+    match cli.format() {
+        Format::Boards | Format::Json if matches!(progress, Progress::Live { .. }) => {
+            // the search draws over the whole terminal, the solutions can only come after it
+            let mut distinct = Distinct::new(true);
+            let solutions = if cli.distinct {
+                let mut solutions = Vec::new();
+                distinct.search(solver.as_ref(), &board, &pieces, cli.limit(), &mut |s| {
+                    solutions.push(s.clone());
+                    true
+                });
+                solutions
+            } else {
+                solver.solve_up_to(&board, &pieces, cli.limit())
+            };
+            let elapsed = now.elapsed();
+            // json is only the solutions, one per line
+            let json = cli.format() == Format::Json;
+            if !json {
+                writeln!(out, "{}", date.format("%A %-d %B %Y"))?;
+            }
+            for (number, s) in solutions.iter().enumerate() {
+                show_solution(out, cli, date, number + 1, s, &pieces)?;
+            }
+            if json {
+                return Ok(());
+            }
+            if cli.distinct {
+                writeln!(out, "Got {distinct}")?;
+            } else {
+                writeln!(out, "Got {} solutions", solutions.len())?;
+            }
+            writeln!(out, "Solved in {} ms", elapsed.as_millis())?;
+        }
+        Format::Boards | Format::Json => {
+            let json = cli.format() == Format::Json;
+            if !json {
+                writeln!(out, "{}", date.format("%A %-d %B %Y"))?;
+            }
+            let mut count = 0;
+            let mut distinct = Distinct::new(true);
+            let mut written = Ok(());
+            let mut on_solution = |s: &board::Board| {
+                count += 1;
+                written = show_solution(out, cli, date, count, s, &pieces);
+                written.is_ok() && count < cli.limit()
+            };
+            if cli.distinct {
+                let limit = cli.limit();
+                distinct.search(solver.as_ref(), &board, &pieces, limit, &mut on_solution);
+            } else if cli.limit() > 0 {
+                solver.for_each_solution(&board, &pieces, &mut on_solution);
+            }
+            written?;
+            if json {
+                return Ok(());
+            }
+            if cli.distinct {
+                writeln!(out, "Got {distinct}")?;
+            } else {
+                writeln!(out, "Got {count} solutions")?;
+            }
+            writeln!(out, "Solved in {} ms", now.elapsed().as_millis())?;
+        }
+        Format::Summary if cli.histogram() => {
+            // the histogram needs to know where each piece went, only the backtracking keeps that
+            let histogram = Backtracking { progress }.histogram(&board, &pieces);
+            let elapsed = now.elapsed();
+            writeln!(
+                out,
+                "{} {}: {} solutions in {} ms",
+                date,
+                date.weekday(),
+                histogram.solutions,
+                elapsed.as_millis()
+            )?;
+            print_histogram(out, &histogram)?;
+        }
+        Format::Summary if cli.distinct => {
+            let distinct = count_distinct(cli, solver.as_ref(), &board, &pieces);
+            let elapsed = now.elapsed();
+            writeln!(
+                out,
+                "{} {}: {} in {} ms",
+                date,
+                date.weekday(),
+                distinct,
+                elapsed.as_millis()
+            )?;
+        }
+        Format::Summary => {
+            let count = count_solutions(cli, solver.as_ref(), &board, &pieces);
+            let elapsed = now.elapsed();
+            writeln!(
+                out,
+                "{} {}: {} solutions in {} ms",
+                date,
+                date.weekday(),
+                count,
+                elapsed.as_millis()
+            )?;
+        }
+        Format::Csv => {
+            let (count, distinct) = if cli.distinct {
+                let distinct = count_distinct(cli, solver.as_ref(), &board, &pieces);
+                (distinct.raw(), Some(distinct.distinct()))
+            } else {
+                (count_solutions(cli, solver.as_ref(), &board, &pieces), None)
+            };
+            let row = output::csv_row(date, count, distinct, now.elapsed());
+            writeln!(out, "{row}")?;
+        }
+    }
+    Ok(())
+}
+
+// prints the solution in the format asked for, and saves it as DIR/2024-03-02-1.svg with --svg
+fn show_solution(
+    out: &mut impl Write,
+    cli: &Cli,
+    date: NaiveDate,
+    number: usize,
+    solution: &board::Board,
+    pieces: &[Piece],
+) -> io::Result<()> {
+    match cli.format() {
+        Format::Json => writeln!(out, "{}", output::to_json(date, solution, pieces))?,
+        _ if cli.color => {
+            let colored = Colored::new(solution).with_all_labels(cli.all_labels);
+            writeln!(out, "{colored}")?;
+        }
+        _ => writeln!(out, "{solution}")?,
+    }
+    if let Some(dir) = &cli.svg {
+        let path = dir.join(format!("{date}-{number}.svg"));
+        if let Err(error) = svg::save(solution, &path) {
+            eprintln!("Could not write {}: {error}", path.display());
+        }
+    }
+    Ok(())
+}
+
+// for each piece, how many solutions have the first square of its top line on each cell
+fn print_histogram(out: &mut impl Write, histogram: &Histogram) -> io::Result<()> {
+    for (id, orientations) in histogram.pieces.iter().zip(&histogram.placements) {
+        writeln!(out, "{id}:")?;
+        let cells: Vec<usize> = (0..orientations[0].len())
+            .map(|cell| orientations.iter().map(|o| o[cell]).sum())
+            .collect();
+        for line in cells.chunks(histogram.width) {
+            let counts: Vec<String> = line.iter().map(|count| format!("{count:>3}")).collect();
+            writeln!(out, "{}", counts.join(""))?;
+        }
+    }
+    Ok(())
+}
+
+fn count_solutions(
+    cli: &Cli,
+    solver: &dyn Solver,
+    board: &board::Board,
+    pieces: &[Piece],
+) -> usize {
+    let Some(limit) = cli.limit else {
+        return solver.count(board, pieces);
+    };
+    let mut count = 0;
+    if limit > 0 {
+        solver.for_each_solution(board, pieces, &mut |_| {
+            count += 1;
+            count < limit
+        });
+    }
+    count
+}
 
-    let _ = try_placing_pieces(&mut board, &mut pieces, &mut solutions);
+// the solutions have to be looked at to tell which are the same
+fn count_distinct(
+    cli: &Cli,
+    solver: &dyn Solver,
+    board: &board::Board,
+    pieces: &[Piece],
+) -> Distinct {
+    let mut distinct = Distinct::new(true);
+    distinct.search(solver, board, pieces, cli.limit(), &mut |_| true);
+    distinct
+}
 
-    let elapsed = now.elapsed();
-    
-    print!("{}{}", termion::clear::All, termion::cursor::Goto(1, 1));
-    println!("Got {} solutions", solutions.len());
-    for s in solutions {
-        println!("{s}");
+fn run_survey(out: &mut impl Write, cli: &Cli) -> io::Result<()> {
+    let solver = cli.backend.solver(cli.threads, Progress::Quiet);
+    let pieces = cli.pieces();
+    // shared by both callbacks, the searches stop once the output is gone
+    let written = RefCell::new(Ok(()));
+    let count = |board: &board::Board| {
+        if written.borrow().is_ok() {
+            count_solutions(cli, solver.as_ref(), board, &pieces)
+        } else {
+            0
+        }
+    };
+    let report = survey::run(&cli.layout(), cli.limit, count, |entry| {
+        if !cli.quiet && written.borrow().is_ok() {
+            *written.borrow_mut() = writeln!(out, "{entry}: {} solutions", entry.solutions);
+        }
+    });
+    written.into_inner()?;
+    match report {
+        Ok(report) => writeln!(out, "{report}")?,
+        Err(error) => eprintln!("Could not survey the board: {error}"),
     }
-    println!("Solved in {} ms", elapsed.as_millis());
+    Ok(())
 }
 
-fn get_date() -> (u8, u8, u8) {
+fn run_play(out: &mut impl Write, cli: &Cli) -> io::Result<()> {
+    let Some(date) = cli.dates().and_then(|dates| dates.first().copied()) else {
+        return Ok(());
+    };
+    let mut board = board::Board::from_layout(cli.layout());
+    if let Err(error) = board.set_date_from(date) {
+        eprintln!("{date}: {error}");
+        return Ok(());
+    }
+    let title = date.format("%A %-d %B %Y").to_string();
+    match play::play(board, cli.pieces(), &title) {
+        Ok(true) => writeln!(out, "Solved {title}")?,
+        Ok(false) => (),
+        Err(error) => eprintln!("Could not play on this terminal: {error}"),
+    }
+    Ok(())
+}
+
+// None once the input is closed or cannot be read
+fn get_date(out: &mut impl Write) -> io::Result<Option<NaiveDate>> {
     let mut input: String = String::new();
     loop {
-        println!("What day would you like to solve for?");
-        io::stdin().read_line(&mut input).unwrap_or_else(|error| {
-            panic!("Could not read from input {error}");
-        });
-        let result = match parse(&input.trim()) {
+        writeln!(out, "What day would you like to solve for?")?;
+        input.clear();
+        match io::stdin().read_line(&mut input) {
+            Ok(0) => return Ok(None),
+            Ok(_) => (),
+            Err(error) => {
+                eprintln!("Could not read from input {error}");
+                return Ok(None);
+            }
+        }
+        match cli::parse_date(&input) {
             Ok(d) => {
-                println!("That was a {}", d.weekday());
-                (
-                    d.weekday().number_from_monday() as u8,
-                    d.day() as u8,
-                    d.month() as u8,
-                )
+                writeln!(out, "That was a {}", d.weekday())?;
+                return Ok(Some(d));
             }
             Err(error) => {
-                println!(
-                    "Could not parse the data {}, got [{}]",
-                    error.to_string(),
-                    input
-                );
+                writeln!(out, "{error}")?;
                 continue;
             }
         };
-        return result;
-    }
-}
-
-const CHECK_FOR_SOLVABILITY_THRESH: usize = 5;
-
-fn try_placing_pieces<'a>(
-    b: &'a mut board::Board,
-    pieces: &mut Vec<char>,
-    solutions: &mut Vec<board::Board>,
-) {
-    print!("{}{}{b}", termion::clear::All, termion::cursor::Goto(1, 1));
-    if b.is_full() {
-        return;
-    } else {
-        for piece_index in 0..pieces.len() {
-            let this_piece = pieces[piece_index];
-            let orientations = piece::get_piece(pieces[piece_index])
-                .expect("We should be able to get orientations")
-                .possible_pieces;
-            for oriented_piece in orientations {
-                if b.place_piece_on_top_left(&oriented_piece) {
-
-                    if b.is_full() {
-                        solutions.push(b.clone());
-                        b.remove_piece(this_piece);
-                        continue;
-                    }
-
-                    if pieces.len() <= CHECK_FOR_SOLVABILITY_THRESH && !b.is_solvable() {
-                        b.remove_piece(this_piece);
-                        continue;
-                    }
-
-                    pieces.remove(piece_index);
-                    try_placing_pieces(b, pieces, solutions);
-
-                    pieces.insert(piece_index, this_piece);
-                    b.remove_piece(this_piece);
-                }
-            }
-        }
-        return;
     }
 }
@@ -0,0 +1,179 @@
+use std::time::Duration;
+
+use chrono::NaiveDate;
+
+use crate::board::Board;
+use crate::piece::Piece;
+
+/// Where a piece is on a solved board
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    /// Id of the piece
+    pub id: char,
+    /// Index of the way it is turned in [`Piece::possible_pieces`]
+    pub orientation: usize,
+    /// Column of the top left corner of the smallest box around the piece
+    pub column: usize,
+    /// Line of the top left corner of the smallest box around the piece
+    pub line: usize,
+}
+
+/// Where each of the pieces is on the board, in the order of `pieces`. Pieces that are not on
+/// the board, or not turned in any of their possible ways, are left out.
+pub fn placements(solution: &Board, pieces: &[Piece]) -> Vec<Placement> {
+    pieces
+        .iter()
+        .filter_map(|piece| {
+            let squares: Vec<(usize, usize)> = (0..solution.height())
+                .flat_map(|line| (0..solution.width()).map(move |column| (column, line)))
+                .filter(|&(column, line)| solution.cell(column, line) == piece.id)
+                .collect();
+            let column = squares.iter().map(|&(column, _)| column).min()?;
+            let line = squares.iter().map(|&(_, line)| line).min()?;
+            let width = squares.iter().map(|&(c, _)| c).max()? - column + 1;
+            let height = squares.iter().map(|&(_, l)| l).max()? - line + 1;
+
+            let mut pattern = vec![vec!['0'; width]; height];
+            for &(c, l) in &squares {
+                pattern[l - line][c - column] = piece.id;
+            }
+            let orientation = piece
+                .possible_pieces
+                .iter()
+                .position(|oriented| oriented.pattern == pattern)?;
+            Some(Placement {
+                id: piece.id,
+                orientation,
+                column,
+                line,
+            })
+        })
+        .collect()
+}
+
+/// A solution as a JSON object on a single line, with the date, where each piece is and the
+/// board line by line as [`Board`] stores it:
+///
+/// ```text
+/// {"date":"2024-01-01","pieces":[{"id":"t","orientation":0,"column":1,"line":0},...],
+///  "grid":["Xttt0...",...]}
+/// ```
+pub fn to_json(date: NaiveDate, solution: &Board, pieces: &[Piece]) -> String {
+    // ids are letters or digits and the grid only holds ids, '0' and 'X', nothing to escape
+    let pieces: Vec<String> = placements(solution, pieces)
+        .iter()
+        .map(|p| {
+            format!(
+                r#"{{"id":"{}","orientation":{},"column":{},"line":{}}}"#,
+                p.id, p.orientation, p.column, p.line
+            )
+        })
+        .collect();
+    let grid: Vec<String> = (0..solution.height())
+        .map(|line| {
+            let row: String = (0..solution.width())
+                .map(|column| solution.cell(column, line))
+                .collect();
+            format!(r#""{row}""#)
+        })
+        .collect();
+    format!(
+        r#"{{"date":"{}","pieces":[{}],"grid":[{}]}}"#,
+        date.format("%Y-%m-%d"),
+        pieces.join(","),
+        grid.join(",")
+    )
+}
+
+/// First line of the CSV summary, with a column for the distinct solutions if asked
+pub fn csv_header(distinct: bool) -> &'static str {
+    if distinct {
+        "date,solutions,distinct,milliseconds"
+    } else {
+        "date,solutions,milliseconds"
+    }
+}
+
+/// One line of the CSV summary, see [`csv_header`]
+pub fn csv_row(
+    date: NaiveDate,
+    solutions: usize,
+    distinct: Option<usize>,
+    time: Duration,
+) -> String {
+    let distinct = distinct.map_or(String::new(), |distinct| format!("{distinct},"));
+    format!(
+        "{},{solutions},{distinct}{}",
+        date.format("%Y-%m-%d"),
+        time.as_millis()
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::piece::{self, Rules};
+    use crate::piece_set::PieceSet;
+    use crate::solver::{Backtracking, Solver};
+
+    #[test]
+    fn placements_give_back_the_board() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let pieces = piece::all_pieces();
+        for solution in Backtracking::default().solve_all(&Board::for_date(date), &pieces) {
+            let placements = placements(&solution, &pieces);
+            assert_eq!(placements.len(), pieces.len());
+
+            let mut b = Board::for_date(date);
+            for (p, piece) in placements.iter().zip(&pieces) {
+                assert_eq!(p.id, piece.id);
+                let pattern = &piece.possible_pieces[p.orientation].pattern;
+                for (l, row) in pattern.iter().enumerate() {
+                    for (c, &square) in row.iter().enumerate() {
+                        if square != '0' {
+                            b.set_cell(p.column + c, p.line + l, square);
+                        }
+                    }
+                }
+            }
+            assert_eq!(b, solution);
+        }
+    }
+
+    #[test]
+    fn json() {
+        let pieces = "aaa\n\nbbb"
+            .parse::<PieceSet>()
+            .unwrap()
+            .pieces(Rules::OneSided);
+        // a standing in the first column, b lying on the top line next to it
+        let mut b = Board::with_size(4, 3).unwrap();
+        for line in 0..3 {
+            b.set_cell(0, line, 'a');
+        }
+        for column in 1..4 {
+            b.set_cell(column, 0, 'b');
+        }
+        let date = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        assert_eq!(
+            to_json(date, &b, &pieces),
+            concat!(
+                r#"{"date":"2024-03-02","pieces":["#,
+                r#"{"id":"a","orientation":1,"column":0,"line":0},"#,
+                r#"{"id":"b","orientation":0,"column":1,"line":0}"#,
+                r#"],"grid":["abbb","a000","a000"]}"#
+            )
+        );
+        assert_eq!(pieces[0].possible_pieces[1].pattern.len(), 3);
+    }
+
+    #[test]
+    fn csv() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        let time = Duration::from_millis(42);
+        assert_eq!(csv_header(false), "date,solutions,milliseconds");
+        assert_eq!(csv_row(date, 8, None, time), "2024-03-02,8,42");
+        assert_eq!(csv_header(true), "date,solutions,distinct,milliseconds");
+        assert_eq!(csv_row(date, 8, Some(2), time), "2024-03-02,8,2,42");
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use crate::solver::{self, Backtracking, Solver};
+use crate::{board, piece};
+
+// how many pieces get placed before handing the branches over to the threads
+const SPLIT_DEPTH: usize = 2;
+
+struct Branch {
+    board: board::Board,
+    pieces: Vec<piece::Piece>,
+}
+
+// what the first levels of the search tree lead to, kept in the order the search meets them
+enum Node {
+    Branch(Branch),
+    Solution(board::Board),
+}
+
+// same as one level of the Backtracking search, but every piece that fits gives a new node
+fn expand(branch: Branch) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    if branch.board.is_full() {
+        return nodes;
+    }
+
+    for (piece_index, piece) in branch.pieces.iter().enumerate() {
+        for oriented_piece in &piece.possible_pieces {
+            let mut b = branch.board.clone();
+            if !b.place_piece_on_top_left(oriented_piece) {
+                continue;
+            }
+            if b.is_full() {
+                nodes.push(Node::Solution(b));
+                continue;
+            }
+
+            let mut pieces = branch.pieces.clone();
+            pieces.remove(piece_index);
+            if branch.pieces.len() <= solver::CHECK_FOR_SOLVABILITY_THRESH
+                && !b.is_solvable_with(pieces.iter().map(piece::Piece::size))
+            {
+                continue;
+            }
+            nodes.push(Node::Branch(Branch { board: b, pieces }));
+        }
+    }
+    nodes
+}
+
+/// [`Backtracking`] with `threads` threads, each of them taking the next branch of the search
+/// tree that nobody has explored yet. The solutions are handed over in the same order as a
+/// single [`Backtracking`] would find them.
+pub struct Parallel {
+    /// Number of threads searching at the same time
+    pub threads: usize,
+}
+
+impl Parallel {
+    // the branches left once SPLIT_DEPTH pieces are on the board, with the solutions found on
+    // the way in between them, where a single Backtracking would find them
+    fn split(b: &board::Board, pieces: &[piece::Piece]) -> Vec<Node> {
+        let mut nodes = vec![Node::Branch(Branch {
+            board: b.clone(),
+            pieces: pieces.to_vec(),
+        })];
+        for _ in 0..SPLIT_DEPTH {
+            nodes = nodes
+                .into_iter()
+                .flat_map(|node| match node {
+                    Node::Branch(branch) => expand(branch),
+                    Node::Solution(_) => vec![node],
+                })
+                .collect();
+        }
+        nodes
+    }
+}
+
+impl Solver for Parallel {
+    fn for_each_solution(
+        &self,
+        b: &board::Board,
+        pieces: &[piece::Piece],
+        on_solution: &mut dyn FnMut(&board::Board) -> bool,
+    ) -> bool {
+        let nodes = Parallel::split(b, pieces);
+        let next_branch = AtomicUsize::new(0);
+        let stop = AtomicBool::new(false);
+        let (sender, receiver) = mpsc::channel::<(usize, Vec<board::Board>)>();
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads.max(1) {
+                let sender = sender.clone();
+                let (next_branch, stop, nodes) = (&next_branch, &stop, &nodes);
+                scope.spawn(move || loop {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let index = next_branch.fetch_add(1, Ordering::Relaxed);
+                    let mut found: Vec<board::Board> = Vec::new();
+                    match nodes.get(index) {
+                        Some(Node::Branch(branch)) => {
+                            Backtracking::default().for_each_solution(
+                                &branch.board,
+                                &branch.pieces,
+                                &mut |s| {
+                                    found.push(s.clone());
+                                    !stop.load(Ordering::Relaxed)
+                                },
+                            );
+                        }
+                        Some(Node::Solution(s)) => found.push(s.clone()),
+                        None => break,
+                    }
+                    if sender.send((index, found)).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(sender);
+
+            // nodes can finish in any order, keep them until it is their turn
+            let mut finished: BTreeMap<usize, Vec<board::Board>> = BTreeMap::new();
+            let mut next_to_report = 0;
+            for (index, found) in receiver {
+                finished.insert(index, found);
+                while let Some(found) = finished.remove(&next_to_report) {
+                    next_to_report += 1;
+                    for s in &found {
+                        if !on_solution(s) {
+                            stop.store(true, Ordering::Relaxed);
+                            return false;
+                        }
+                    }
+                }
+            }
+            true
+        })
+    }
+
+    fn count(&self, b: &board::Board, pieces: &[piece::Piece]) -> usize {
+        let nodes = Parallel::split(b, pieces);
+        let next_branch = AtomicUsize::new(0);
+        let count = AtomicUsize::new(0);
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads.max(1) {
+                let (next_branch, count, nodes) = (&next_branch, &count, &nodes);
+                scope.spawn(move || {
+                    while let Some(node) = nodes.get(next_branch.fetch_add(1, Ordering::Relaxed)) {
+                        let found = match node {
+                            Node::Branch(branch) => {
+                                Backtracking::default().count(&branch.board, &branch.pieces)
+                            }
+                            Node::Solution(_) => 1,
+                        };
+                        count.fetch_add(found, Ordering::Relaxed);
+                    }
+                });
+            }
+        });
+        count.into_inner()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::piece::Rules;
+    use crate::piece_set::PieceSet;
+
+    fn board_for(week_day: u8, day: u8, month: u8) -> board::Board {
+        let mut board = board::Board::new();
+        board.set_date(week_day, day, month).unwrap();
+        board
+    }
+
+    #[test]
+    fn same_solutions_as_single_thread() {
+        for (week_day, day, month) in [(1, 1, 1), (6, 2, 3), (7, 14, 7)] {
+            let board = board_for(week_day, day, month);
+            let pieces = piece::all_pieces();
+
+            let expected = Backtracking::default().solve_up_to(&board, &pieces, usize::MAX);
+            let found = Parallel { threads: 4 }.solve_up_to(&board, &pieces, usize::MAX);
+            assert_eq!(found, expected);
+            assert_eq!(
+                Parallel { threads: 4 }.count(&board, &pieces),
+                expected.len()
+            );
+        }
+    }
+
+    #[test]
+    fn solutions_while_splitting() {
+        // c and d then aa fill the board three pieces deep, but the search meets them before aa
+        // and bb, which fill it while the board is being split
+        let pieces = "c\n\nd\n\naa\n\nbb"
+            .parse::<PieceSet>()
+            .unwrap()
+            .pieces(Rules::Free);
+        let board = board::Board::with_size(2, 2).unwrap();
+        let expected = Backtracking::default().solve_up_to(&board, &pieces, usize::MAX);
+        let found = Parallel { threads: 4 }.solve_up_to(&board, &pieces, usize::MAX);
+        assert_eq!(found, expected);
+        let count = Parallel { threads: 4 }.count(&board, &pieces);
+        assert_eq!(count, expected.len());
+    }
+
+    #[test]
+    fn limit() {
+        let board = board_for(1, 1, 1);
+        let pieces = piece::all_pieces();
+        let expected = Backtracking::default().solve_up_to(&board, &pieces, 3);
+        let found = Parallel { threads: 4 }.solve_up_to(&board, &pieces, 3);
+        assert_eq!(found, expected);
+    }
+}
@@ -1,27 +1,265 @@
+use crate::piece_set::PieceSet;
+
+/// A piece turned one way
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OrientedPiece {
-    pub pattern: [[char; 5]; 5],
+    /// The squares of the piece hold its id, the others `'0'`, in the smallest box around the
+    /// piece. Every line has the same length.
+    pub pattern: Vec<Vec<char>>,
+    /// Column of the first square of the top line
     pub top_index: u8,
 }
 
+/// A piece of the puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Piece {
+    /// The letter the piece looks like
     pub id: char,
+    /// Every way the piece can be turned
     pub possible_pieces: Vec<OrientedPiece>,
 }
 
-pub fn all_pieces() -> Vec<char> {
-    vec!['t','u','z','i','y','l','j','v','q','f']
+/// Which ways the pieces can be turned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rules {
+    /// Pieces are only rotated, like with the wooden puzzle
+    #[default]
+    OneSided,
+    /// Pieces can also be flipped over
+    Free,
+}
+
+/// The pieces of the puzzle, only rotated like with the wooden puzzle
+pub fn all_pieces() -> Vec<Piece> {
+    all_pieces_with(Rules::OneSided)
+}
+
+/// The pieces of the puzzle, turned as the rules allow
+pub fn all_pieces_with(rules: Rules) -> Vec<Piece> {
+    PieceSet::weekday().pieces(rules)
+}
+
+impl OrientedPiece {
+    // squares are (column, line) and can be anywhere, the piece is moved to the top left
+    fn from_squares(id: char, squares: &[(i32, i32)]) -> OrientedPiece {
+        let min_column = squares.iter().map(|&(column, _)| column).min().unwrap_or(0);
+        let min_line = squares.iter().map(|&(_, line)| line).min().unwrap_or(0);
+        let width = squares
+            .iter()
+            .map(|&(column, _)| column - min_column + 1)
+            .max()
+            .unwrap_or(0);
+        let height = squares
+            .iter()
+            .map(|&(_, line)| line - min_line + 1)
+            .max()
+            .unwrap_or(0);
+
+        let mut pattern = vec![vec!['0'; width as usize]; height as usize];
+        for &(column, line) in squares {
+            pattern[(line - min_line) as usize][(column - min_column) as usize] = id;
+        }
+        let top_index = pattern
+            .first()
+            .and_then(|row| row.iter().position(|&square| square != '0'))
+            .unwrap_or(0) as u8;
+
+        OrientedPiece { pattern, top_index }
+    }
 }
 
+impl Piece {
+    /// Number of squares of the piece
+    pub fn size(&self) -> usize {
+        self.possible_pieces.first().map_or(0, |oriented_piece| {
+            oriented_piece
+                .pattern
+                .iter()
+                .flatten()
+                .filter(|&&square| square != '0')
+                .count()
+        })
+    }
+
+    /// The piece drawn line by line with `id` for its squares and `'0'` around them, along
+    /// with every way the rules let it be turned
+    pub fn new(id: char, shape: &[&str], rules: Rules) -> Piece {
+        let mut squares: Vec<(i32, i32)> = shape
+            .iter()
+            .enumerate()
+            .flat_map(|(line, row)| {
+                row.chars()
+                    .enumerate()
+                    .filter(|&(_, square)| square != '0')
+                    .map(move |(column, _)| (column as i32, line as i32))
+            })
+            .collect();
+
+        let sides = match rules {
+            Rules::OneSided => 1,
+            Rules::Free => 2,
+        };
+        let mut possible_pieces: Vec<OrientedPiece> = Vec::new();
+        for _ in 0..sides {
+            for _ in 0..4 {
+                // symmetric pieces look the same after some of the turns
+                let oriented_piece = OrientedPiece::from_squares(id, &squares);
+                if !possible_pieces.contains(&oriented_piece) {
+                    possible_pieces.push(oriented_piece);
+                }
+                // a quarter turn clockwise, lines go down
+                squares = squares.iter().map(|&(column, line)| (-line, column)).collect();
+            }
+            // upside down, the left side goes to the right
+            squares = squares.iter().map(|&(column, line)| (-column, line)).collect();
+        }
+
+        Piece { id, possible_pieces }
+    }
+}
+
+/// The piece with that id, if it is part of the puzzle, only rotated
 pub fn get_piece(id: char) -> Option<Piece> {
-    let mut result = Piece {
-        id,
-        possible_pieces: vec![],
+    PieceSet::weekday().get(id, Rules::OneSided)
+}
+
+/// The colour a piece is drawn with, as red, green and blue. Every letter and digit gets its
+/// own, spread around the colour wheel so that pieces next to each other stand out.
+pub fn color(id: char) -> (u8, u8, u8) {
+    let index = match id {
+        'a'..='z' => id as u32 - 'a' as u32,
+        'A'..='Z' => id as u32 - 'A' as u32 + 26,
+        '0'..='9' => id as u32 - '0' as u32 + 52,
+        _ => 0,
     };
+    // the golden angle keeps the hues of ids that follow each other far apart
+    let hue = (f64::from(index) * 137.508) % 360.0;
+    let (saturation, lightness) = (0.65, 0.6);
+
+    // hsl to rgb
+    let chroma = (1.0 - (2.0 * lightness - 1.0_f64).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let (red, green, blue) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let byte = |value: f64| ((value + m) * 255.0).round() as u8;
+    (byte(red), byte(green), byte(blue))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn colors() {
+        let ids = "tuziyljvqf";
+        for a in ids.chars() {
+            for b in ids.chars().filter(|&b| b != a) {
+                assert_ne!(color(a), color(b));
+            }
+        }
+        assert_eq!(color('a'), (219, 87, 87));
+    }
+
+    #[test]
+    fn same_as_hand_written() {
+        for id in all_pieces().iter().map(|p| p.id) {
+            let generated = get_piece(id).unwrap().possible_pieces;
+            let expected = hand_written(id).unwrap();
+            let expected: Vec<OrientedPiece> = expected.iter().map(HandWritten::cropped).collect();
+            assert_eq!(generated.len(), expected.len(), "piece {id}");
+            for oriented_piece in &expected {
+                assert!(generated.contains(oriented_piece), "piece {id}: {oriented_piece:?}");
+            }
+        }
+        assert!(get_piece('x').is_none());
+    }
+
+    #[test]
+    fn five_squares() {
+        for piece in all_pieces() {
+            for oriented_piece in &piece.possible_pieces {
+                let squares = oriented_piece.pattern.iter().flatten();
+                let count = squares.filter(|&&c| c == piece.id).count();
+                assert_eq!(count, 5, "piece {}", piece.id);
+            }
+        }
+    }
 
-    match id {
-        't' => {
-            result.possible_pieces = vec![
-                OrientedPiece {
+    #[test]
+    fn symmetries() {
+        let x = Piece::new('x', &["0x0", "xxx", "0x0"], Rules::Free);
+        assert_eq!(x.possible_pieces.len(), 1);
+        assert_eq!(x.possible_pieces[0].top_index, 1);
+
+        let i = Piece::new('i', &["iiiii"], Rules::OneSided);
+        assert_eq!(i.possible_pieces.len(), 2);
+        assert_eq!(i.possible_pieces[1].pattern[4][0], 'i');
+    }
+
+    #[test]
+    fn flipped_over() {
+        let one_sided = all_pieces();
+        let free = all_pieces_with(Rules::Free);
+        let count = |pieces: &[Piece]| -> Vec<usize> {
+            pieces.iter().map(|p| p.possible_pieces.len()).collect()
+        };
+        // t, u, v and i are the same upside down
+        assert_eq!(count(&one_sided), [4, 4, 2, 2, 4, 4, 4, 4, 4, 4]);
+        assert_eq!(count(&free), [4, 4, 4, 2, 8, 8, 8, 4, 8, 8]);
+
+        // every one sided orientation is still there, and the mirror of l is one of the others
+        for (piece, free_piece) in one_sided.iter().zip(&free) {
+            assert_eq!(piece.id, free_piece.id);
+            assert!(piece
+                .possible_pieces
+                .iter()
+                .all(|o| free_piece.possible_pieces.contains(o)));
+        }
+        let mirrored_l = Piece::new('l', &["ll", "l0", "l0", "l0"], Rules::OneSided);
+        assert!(!one_sided[5].possible_pieces.contains(&mirrored_l.possible_pieces[0]));
+        assert!(free[5].possible_pieces.contains(&mirrored_l.possible_pieces[0]));
+    }
+
+    // an orientation as it was written by hand, in a 5x5 box
+    struct HandWritten {
+        pattern: [[char; 5]; 5],
+        top_index: u8,
+    }
+
+    impl HandWritten {
+        // the same without the empty lines and columns at the bottom and on the right
+        fn cropped(&self) -> OrientedPiece {
+            let used = |square: &char| *square != '0';
+            let height = self.pattern.iter().rposition(|row| row.iter().any(used)).unwrap() + 1;
+            let width = self
+                .pattern
+                .iter()
+                .filter_map(|row| row.iter().rposition(used))
+                .max()
+                .unwrap()
+                + 1;
+            OrientedPiece {
+                pattern: self.pattern[..height]
+                    .iter()
+                    .map(|row| row[..width].to_vec())
+                    .collect(),
+                top_index: self.top_index,
+            }
+        }
+    }
+
+    // the orientations as they were written by hand before get_piece worked them out
+    fn hand_written(id: char) -> Option<Vec<HandWritten>> {
+        let result = match id {
+            't' => vec![
+                HandWritten {
                     pattern: [
                         ['t', 't', 't', '0', '0'],
                         ['0', 't', '0', '0', '0'],
@@ -31,7 +269,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['t', '0', '0', '0', '0'],
                         ['t', 't', 't', '0', '0'],
@@ -41,7 +279,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['0', 't', '0', '0', '0'],
                         ['0', 't', '0', '0', '0'],
@@ -51,7 +289,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 1,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['0', '0', 't', '0', '0'],
                         ['t', 't', 't', '0', '0'],
@@ -61,11 +299,9 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 2,
                 },
-            ];
-        }
-        'u' => {
-            result.possible_pieces = vec![
-                OrientedPiece {
+            ],
+            'u' => vec![
+                HandWritten {
                     pattern: [
                         ['u', '0', 'u', '0', '0'],
                         ['u', 'u', 'u', '0', '0'],
@@ -75,7 +311,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['u', 'u', '0', '0', '0'],
                         ['u', '0', '0', '0', '0'],
@@ -85,7 +321,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['u', 'u', 'u', '0', '0'],
                         ['u', '0', 'u', '0', '0'],
@@ -95,7 +331,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['u', 'u', '0', '0', '0'],
                         ['0', 'u', '0', '0', '0'],
@@ -105,11 +341,9 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-            ];
-        }
-        'z' => {
-            result.possible_pieces = vec![
-                OrientedPiece {
+            ],
+            'z' => vec![
+                HandWritten {
                     pattern: [
                         ['z', 'z', '0', '0', '0'],
                         ['0', 'z', '0', '0', '0'],
@@ -119,7 +353,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['0', '0', 'z', '0', '0'],
                         ['z', 'z', 'z', '0', '0'],
@@ -129,11 +363,9 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 2,
                 },
-            ];
-        }
-        'i' => {
-            result.possible_pieces = vec![
-                OrientedPiece {
+            ],
+            'i' => vec![
+                HandWritten {
                     pattern: [
                         ['i', '0', '0', '0', '0'],
                         ['i', '0', '0', '0', '0'],
@@ -143,7 +375,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['i', 'i', 'i', 'i', 'i'],
                         ['0', '0', '0', '0', '0'],
@@ -153,11 +385,9 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-            ];
-        }
-        'y' => {
-            result.possible_pieces = vec![
-                OrientedPiece {
+            ],
+            'y' => vec![
+                HandWritten {
                     pattern: [
                         ['0', 'y', '0', '0', '0'],
                         ['y', 'y', '0', '0', '0'],
@@ -167,7 +397,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 1,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['0', '0', 'y', '0', '0'],
                         ['y', 'y', 'y', 'y', '0'],
@@ -177,7 +407,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 2,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['y', '0', '0', '0', '0'],
                         ['y', '0', '0', '0', '0'],
@@ -187,7 +417,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['y', 'y', 'y', 'y', '0'],
                         ['0', 'y', '0', '0', '0'],
@@ -196,12 +426,10 @@ pub fn get_piece(id: char) -> Option<Piece> {
                         ['0', '0', '0', '0', '0'],
                     ],
                     top_index: 0,
-                }
-            ];
-        }
-        'l' => {
-            result.possible_pieces = vec![
-                OrientedPiece {
+                },
+            ],
+            'l' => vec![
+                HandWritten {
                     pattern: [
                         ['l', 'l', '0', '0', '0'],
                         ['0', 'l', '0', '0', '0'],
@@ -211,7 +439,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['0', '0', '0', 'l', '0'],
                         ['l', 'l', 'l', 'l', '0'],
@@ -221,7 +449,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 3,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['l', '0', '0', '0', '0'],
                         ['l', '0', '0', '0', '0'],
@@ -231,7 +459,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['l', 'l', 'l', 'l', '0'],
                         ['l', '0', '0', '0', '0'],
@@ -240,12 +468,10 @@ pub fn get_piece(id: char) -> Option<Piece> {
                         ['0', '0', '0', '0', '0'],
                     ],
                     top_index: 0,
-                }
-            ]
-        }
-        'j' => {
-            result.possible_pieces = vec![
-                OrientedPiece {
+                },
+            ],
+            'j' => vec![
+                HandWritten {
                     pattern: [
                         ['j', '0', '0', '0', '0'],
                         ['j', 'j', '0', '0', '0'],
@@ -255,7 +481,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['0', '0', 'j', 'j', '0'],
                         ['j', 'j', 'j', '0', '0'],
@@ -265,7 +491,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 2,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['j', '0', '0', '0', '0'],
                         ['j', '0', '0', '0', '0'],
@@ -275,7 +501,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['0', 'j', 'j', 'j', '0'],
                         ['j', 'j', '0', '0', '0'],
@@ -284,12 +510,10 @@ pub fn get_piece(id: char) -> Option<Piece> {
                         ['0', '0', '0', '0', '0'],
                     ],
                     top_index: 1,
-                }
-            ]
-        }
-        'v' => {
-            result.possible_pieces = vec![
-                OrientedPiece {
+                },
+            ],
+            'v' => vec![
+                HandWritten {
                     pattern: [
                         ['v', 'v', 'v', '0', '0'],
                         ['v', '0', '0', '0', '0'],
@@ -299,7 +523,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['v', 'v', 'v', '0', '0'],
                         ['0', '0', 'v', '0', '0'],
@@ -309,7 +533,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['0', '0', 'v', '0', '0'],
                         ['0', '0', 'v', '0', '0'],
@@ -319,7 +543,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 2,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['v', '0', '0', '0', '0'],
                         ['v', '0', '0', '0', '0'],
@@ -328,12 +552,10 @@ pub fn get_piece(id: char) -> Option<Piece> {
                         ['0', '0', '0', '0', '0'],
                     ],
                     top_index: 0,
-                }
-            ]
-        }
-        'q' => {
-            result.possible_pieces = vec![
-                OrientedPiece {
+                },
+            ],
+            'q' => vec![
+                HandWritten {
                     pattern: [
                         ['q', '0', '0', '0', '0'],
                         ['q', 'q', '0', '0', '0'],
@@ -343,17 +565,17 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['q', 'q', 'q', '0', '0'],
                         ['q', 'q', '0', '0', '0'],
-                        ['q', '0', '0', '0', '0'],
+                        ['0', '0', '0', '0', '0'],
                         ['0', '0', '0', '0', '0'],
                         ['0', '0', '0', '0', '0'],
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['q', 'q', '0', '0', '0'],
                         ['q', 'q', '0', '0', '0'],
@@ -363,7 +585,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['0', 'q', 'q', '0', '0'],
                         ['q', 'q', 'q', '0', '0'],
@@ -372,12 +594,10 @@ pub fn get_piece(id: char) -> Option<Piece> {
                         ['0', '0', '0', '0', '0'],
                     ],
                     top_index: 1,
-                }
-            ]
-        }
-        'f' => {
-            result.possible_pieces = vec![
-                OrientedPiece {
+                },
+            ],
+            'f' => vec![
+                HandWritten {
                     pattern: [
                         ['0', 'f', '0', '0', '0'],
                         ['0', 'f', 'f', '0', '0'],
@@ -387,7 +607,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 1,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['f', '0', '0', '0', '0'],
                         ['f', 'f', 'f', '0', '0'],
@@ -397,7 +617,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 0,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['0', 'f', 'f', '0', '0'],
                         ['f', 'f', '0', '0', '0'],
@@ -407,7 +627,7 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 1,
                 },
-                OrientedPiece {
+                HandWritten {
                     pattern: [
                         ['0', 'f', '0', '0', '0'],
                         ['f', 'f', 'f', '0', '0'],
@@ -417,11 +637,9 @@ pub fn get_piece(id: char) -> Option<Piece> {
                     ],
                     top_index: 1,
                 },
-            ]
-        }
-        _ => {
-            return None;
-        }
+            ],
+            _ => return None,
+        };
+        Some(result)
     }
-    Some(result)
 }
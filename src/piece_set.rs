@@ -0,0 +1,343 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::{fs, io};
+
+use crate::layout::{BoardLayout, Cell};
+use crate::piece::{Piece, Rules};
+
+/// The pieces of a puzzle, each drawn once.
+///
+/// A piece set is written piece by piece, with an empty line between them. Each piece is drawn
+/// line by line with its id for its squares and `.` around them, the id being a letter or a
+/// digit other than `X` and `0`:
+///
+/// ```text
+/// ttt
+/// .t.
+/// .t.
+///
+/// u.u
+/// uuu
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PieceSet {
+    // each shape with '0' around its squares, the way Piece::new takes it
+    shapes: Vec<(char, Vec<String>)>,
+}
+
+/// Why a piece set could not be read, or does not fit a board
+#[derive(Debug)]
+pub enum PieceSetError {
+    /// The file could not be read
+    Io(io::Error),
+    /// There is no piece at all
+    Empty,
+    /// A square is neither `.` nor the id of the piece, or the id is `X` or `0`
+    BadSquare {
+        /// Line of the file, from 1
+        line: usize,
+        /// What was found there
+        square: char,
+    },
+    /// The squares of a piece do not all touch each other
+    Disconnected(char),
+    /// Two pieces have the same id
+    DuplicateId(char),
+    /// The pieces do not cover exactly the cells a date leaves free
+    WrongArea {
+        /// Number of squares of all the pieces together
+        pieces: usize,
+        /// Number of cells to cover on the board
+        free_cells: usize,
+    },
+}
+
+impl fmt::Display for PieceSetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            PieceSetError::Io(error) => write!(f, "could not read the pieces: {error}"),
+            PieceSetError::Empty => write!(f, "there are no pieces"),
+            PieceSetError::BadSquare { line, square } => {
+                write!(f, "line {line}: {square:?} is not a square of the piece")
+            }
+            PieceSetError::Disconnected(id) => write!(f, "the squares of {id} do not all touch"),
+            PieceSetError::DuplicateId(id) => write!(f, "{id} is used by more than one piece"),
+            PieceSetError::WrongArea { pieces, free_cells } => write!(
+                f,
+                "the pieces have {pieces} squares but there are {free_cells} cells to cover"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PieceSetError {}
+
+impl From<io::Error> for PieceSetError {
+    fn from(error: io::Error) -> PieceSetError {
+        PieceSetError::Io(error)
+    }
+}
+
+impl PieceSet {
+    /// The ten pentominoes of the calendar with the week days
+    pub fn weekday() -> PieceSet {
+        // get_piece and all_pieces ask for it all the time, only read it once
+        static WEEKDAY: OnceLock<PieceSet> = OnceLock::new();
+        WEEKDAY
+            .get_or_init(|| {
+                include_str!("../pieces/weekday.txt")
+                    .parse()
+                    .expect("the built-in pieces should be valid")
+            })
+            .clone()
+    }
+
+    /// The seven pentominoes and the 2x3 rectangle of A-Puzzle-A-Day
+    pub fn a_puzzle_a_day() -> PieceSet {
+        static A_PUZZLE_A_DAY: OnceLock<PieceSet> = OnceLock::new();
+        A_PUZZLE_A_DAY
+            .get_or_init(|| {
+                include_str!("../pieces/a_puzzle_a_day.txt")
+                    .parse()
+                    .expect("the built-in pieces should be valid")
+            })
+            .clone()
+    }
+
+    /// Reads a piece set from a file, see [`PieceSet`] for the format
+    pub fn from_file(path: impl AsRef<Path>) -> Result<PieceSet, PieceSetError> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// The ids of the pieces, in the order of the file
+    pub fn ids(&self) -> impl Iterator<Item = char> + '_ {
+        self.shapes.iter().map(|&(id, _)| id)
+    }
+
+    /// Number of squares of all the pieces together
+    pub fn area(&self) -> usize {
+        self.shapes
+            .iter()
+            .flat_map(|(_, rows)| rows.iter())
+            .map(|row| row.chars().filter(|&square| square != '0').count())
+            .sum()
+    }
+
+    /// Every piece with the ways the rules let it be turned
+    pub fn pieces(&self, rules: Rules) -> Vec<Piece> {
+        self.shapes
+            .iter()
+            .map(|(id, rows)| {
+                let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+                Piece::new(*id, &rows, rules)
+            })
+            .collect()
+    }
+
+    /// The piece with that id, turned as the rules allow
+    pub fn get(&self, id: char, rules: Rules) -> Option<Piece> {
+        let (_, rows) = self.shapes.iter().find(|&&(shape_id, _)| shape_id == id)?;
+        let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+        Some(Piece::new(id, &rows, rules))
+    }
+
+    /// Checks that the pieces cover exactly the cells of the layout that are left once a date
+    /// is set, the day, the month and the week day if the layout has them
+    pub fn check_area(&self, layout: &BoardLayout) -> Result<(), PieceSetError> {
+        let cells = (0..layout.height())
+            .flat_map(|line| (0..layout.width()).map(move |column| (column, line)))
+            .filter(|&(column, line)| layout.cell(column, line) != &Cell::Blocked)
+            .count();
+        let date_cells = if layout.has_week_days() { 3 } else { 2 };
+        let free_cells = cells.saturating_sub(date_cells);
+        if self.area() != free_cells {
+            return Err(PieceSetError::WrongArea {
+                pieces: self.area(),
+                free_cells,
+            });
+        }
+        Ok(())
+    }
+}
+
+// whether every square can be reached from the first one going up, down, left or right
+fn is_connected(rows: &[String]) -> bool {
+    let squares: Vec<(usize, usize)> = rows
+        .iter()
+        .enumerate()
+        .flat_map(|(line, row)| {
+            row.chars()
+                .enumerate()
+                .filter(|&(_, square)| square != '0')
+                .map(move |(column, _)| (column, line))
+        })
+        .collect();
+    let mut reached = vec![squares[0]];
+    let mut index = 0;
+    while index < reached.len() {
+        let (column, line) = reached[index];
+        for &square in &squares {
+            if column.abs_diff(square.0) + line.abs_diff(square.1) == 1
+                && !reached.contains(&square)
+            {
+                reached.push(square);
+            }
+        }
+        index += 1;
+    }
+    reached.len() == squares.len()
+}
+
+impl FromStr for PieceSet {
+    type Err = PieceSetError;
+
+    fn from_str(text: &str) -> Result<PieceSet, PieceSetError> {
+        // each piece with the line it starts on
+        let mut drawings: Vec<(usize, Vec<&str>)> = Vec::new();
+        let mut last_was_blank = true;
+        for (index, text_line) in text.lines().enumerate() {
+            let text_line = text_line.trim_end();
+            if text_line.is_empty() {
+                last_was_blank = true;
+                continue;
+            }
+            if last_was_blank {
+                drawings.push((index + 1, Vec::new()));
+            }
+            last_was_blank = false;
+            if let Some((_, rows)) = drawings.last_mut() {
+                rows.push(text_line);
+            }
+        }
+        if drawings.is_empty() {
+            return Err(PieceSetError::Empty);
+        }
+
+        let mut shapes: Vec<(char, Vec<String>)> = Vec::new();
+        for (first_line, drawing) in drawings {
+            // the first square gives the id of the piece
+            let id = drawing
+                .iter()
+                .flat_map(|row| row.chars())
+                .find(|&square| square != '.')
+                .unwrap_or('.');
+            if !id.is_ascii_alphanumeric() || id == 'X' || id == '0' {
+                let line =
+                    first_line + drawing.iter().position(|row| row.contains(id)).unwrap_or(0);
+                return Err(PieceSetError::BadSquare { line, square: id });
+            }
+
+            let mut rows = Vec::new();
+            for (offset, row) in drawing.iter().enumerate() {
+                if let Some(square) = row.chars().find(|&square| square != '.' && square != id) {
+                    return Err(PieceSetError::BadSquare {
+                        line: first_line + offset,
+                        square,
+                    });
+                }
+                rows.push(row.replace('.', "0"));
+            }
+
+            if shapes.iter().any(|&(shape_id, _)| shape_id == id) {
+                return Err(PieceSetError::DuplicateId(id));
+            }
+            if !is_connected(&rows) {
+                return Err(PieceSetError::Disconnected(id));
+            }
+            shapes.push((id, rows));
+        }
+
+        Ok(PieceSet { shapes })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn built_in() {
+        let weekday = PieceSet::weekday();
+        assert_eq!(weekday.ids().collect::<String>(), "tuziyljvqf");
+        assert_eq!(weekday.area(), 50);
+        assert!(weekday.check_area(&BoardLayout::weekday()).is_ok());
+        assert_eq!(weekday.get('u', Rules::OneSided).unwrap().size(), 5);
+        assert!(weekday.get('r', Rules::OneSided).is_none());
+
+        let a_puzzle_a_day = PieceSet::a_puzzle_a_day();
+        assert_eq!(a_puzzle_a_day.area(), 41);
+        assert!(a_puzzle_a_day
+            .check_area(&BoardLayout::a_puzzle_a_day())
+            .is_ok());
+        assert!(matches!(
+            a_puzzle_a_day.check_area(&BoardLayout::weekday()),
+            Err(PieceSetError::WrongArea {
+                pieces: 41,
+                free_cells: 50
+            })
+        ));
+    }
+
+    #[test]
+    fn bad_piece_sets() {
+        assert!(matches!(
+            "\n\n".parse::<PieceSet>(),
+            Err(PieceSetError::Empty)
+        ));
+        assert!(matches!(
+            "aa\n\nbb\nbc".parse::<PieceSet>(),
+            Err(PieceSetError::BadSquare {
+                line: 4,
+                square: 'c'
+            })
+        ));
+        assert!(matches!(
+            "..\n.X".parse::<PieceSet>(),
+            Err(PieceSetError::BadSquare {
+                line: 2,
+                square: 'X'
+            })
+        ));
+        assert!(matches!(
+            "a.a".parse::<PieceSet>(),
+            Err(PieceSetError::Disconnected('a'))
+        ));
+        assert!(matches!(
+            "a.\n.a".parse::<PieceSet>(),
+            Err(PieceSetError::Disconnected('a'))
+        ));
+        assert!(matches!(
+            "aa\n\nbb\n\naa".parse::<PieceSet>(),
+            Err(PieceSetError::DuplicateId('a'))
+        ));
+        assert!(matches!(
+            PieceSet::from_file("no/such/pieces.txt"),
+            Err(PieceSetError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn homemade() {
+        let set: PieceSet = "..a\n.aa\n\nbb\nbb\n".parse().unwrap();
+        assert_eq!(set.ids().collect::<String>(), "ab");
+        assert_eq!(set.area(), 7);
+        let pieces = set.pieces(Rules::Free);
+        assert_eq!(pieces[0].possible_pieces.len(), 4);
+        assert_eq!(pieces[1].possible_pieces.len(), 1);
+        assert_eq!(pieces[0].possible_pieces[0].top_index, 1);
+
+        // pieces are not limited to pentominoes
+        let set: PieceSet = "hhhhhhh\n\nkkk.\n..kk\n..k.".parse().unwrap();
+        assert_eq!(set.area(), 13);
+        let pieces = set.pieces(Rules::OneSided);
+        assert_eq!(pieces[0].size(), 7);
+        assert_eq!(pieces[0].possible_pieces[1].pattern.len(), 7);
+        assert_eq!(pieces[1].possible_pieces[0].pattern, [
+            ['k', 'k', 'k', '0'],
+            ['0', '0', 'k', 'k'],
+            ['0', '0', 'k', '0'],
+        ]);
+    }
+}
@@ -0,0 +1,391 @@
+use std::io::{self, Write};
+
+use termion::color::{Bg, Reset, Rgb};
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use termion::screen::IntoAlternateScreen;
+use termion::style::{Faint, NoFaint};
+
+use crate::board::Board;
+use crate::colored::{Colored, CELL_HEIGHT, CELL_WIDTH};
+use crate::piece::{self, OrientedPiece, Piece};
+
+const HELP: &str = "arrows move, space turns, tab picks another piece, enter drops it, \
+                    u takes it back, q quits";
+
+// a piece on the board, to take it back
+#[derive(Debug, Clone, Copy)]
+struct Dropped {
+    piece: usize,
+    orientation: usize,
+    column: usize,
+    line: usize,
+}
+
+/// Solving a board by hand: a piece is picked, turned and moved over the board, then dropped
+/// where it fits
+#[derive(Debug)]
+pub struct Game {
+    board: Board,
+    pieces: Vec<Piece>,
+    // None once every piece is on the board
+    selected: Option<usize>,
+    orientation: usize,
+    // where the top left corner of the box around the piece is
+    column: usize,
+    line: usize,
+    dropped: Vec<Dropped>,
+    message: Option<String>,
+}
+
+impl Game {
+    /// A game on that board, with the date already set, starting with the first piece
+    pub fn new(board: Board, pieces: Vec<Piece>) -> Game {
+        let selected = (!pieces.is_empty()).then_some(0);
+        Game {
+            board,
+            pieces,
+            selected,
+            orientation: 0,
+            column: 0,
+            line: 0,
+            dropped: Vec::new(),
+            message: None,
+        }
+    }
+
+    /// The board with the pieces dropped so far
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// The piece being moved, turned the way it is
+    pub fn selected(&self) -> Option<(char, &OrientedPiece)> {
+        let piece = &self.pieces[self.selected?];
+        Some((piece.id, &piece.possible_pieces[self.orientation]))
+    }
+
+    /// Where the piece being moved is, as the top left corner of the box around it
+    pub fn position(&self) -> (usize, usize) {
+        (self.column, self.line)
+    }
+
+    /// Why the last drop did not work, if it did not
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Whether every cell is covered
+    pub fn is_won(&self) -> bool {
+        self.board.is_full()
+    }
+
+    fn is_dropped(&self, piece: usize) -> bool {
+        self.dropped.iter().any(|dropped| dropped.piece == piece)
+    }
+
+    /// Picks the next piece that is not on the board yet, or the previous one if `step` is -1
+    pub fn pick(&mut self, step: isize) {
+        let Some(selected) = self.selected else {
+            return;
+        };
+        let count = self.pieces.len() as isize;
+        let next = (1..=count)
+            .map(|offset| (selected as isize + step * offset).rem_euclid(count) as usize)
+            .find(|&index| !self.is_dropped(index));
+        if let Some(next) = next {
+            self.selected = Some(next);
+            self.orientation = 0;
+            self.message = None;
+            self.keep_on_board();
+        }
+    }
+
+    /// Turns the piece to the next of its possible ways, or the previous one if `step` is -1
+    pub fn turn(&mut self, step: isize) {
+        let Some(selected) = self.selected else {
+            return;
+        };
+        let count = self.pieces[selected].possible_pieces.len() as isize;
+        self.orientation = (self.orientation as isize + step).rem_euclid(count) as usize;
+        self.message = None;
+        self.keep_on_board();
+    }
+
+    /// Moves the piece, without letting the box around it leave the board
+    pub fn move_by(&mut self, columns: isize, lines: isize) {
+        self.column = self.column.saturating_add_signed(columns);
+        self.line = self.line.saturating_add_signed(lines);
+        self.message = None;
+        self.keep_on_board();
+    }
+
+    fn keep_on_board(&mut self) {
+        if let Some((_, piece)) = self.selected() {
+            let width = self.board.width().saturating_sub(piece.pattern[0].len());
+            let height = self.board.height().saturating_sub(piece.pattern.len());
+            self.column = self.column.min(width);
+            self.line = self.line.min(height);
+        }
+    }
+
+    /// Whether the piece fits where it is, see [`Board::fits_at`]
+    pub fn fits(&self) -> bool {
+        self.selected()
+            .is_some_and(|(_, piece)| self.board.fits_at(piece, self.column, self.line))
+    }
+
+    /// Puts the piece on the board and picks the next one. Returns false, with a message, if
+    /// it does not fit there.
+    pub fn drop_piece(&mut self) -> bool {
+        let (Some(selected), Some((id, piece))) = (self.selected, self.selected()) else {
+            return false;
+        };
+        let piece = piece.clone();
+        if !self.board.place_piece_at(&piece, self.column, self.line) {
+            self.message = Some(format!("{id} does not fit there"));
+            return false;
+        }
+        self.dropped.push(Dropped {
+            piece: selected,
+            orientation: self.orientation,
+            column: self.column,
+            line: self.line,
+        });
+        self.message = None;
+        if self.dropped.len() == self.pieces.len() {
+            self.selected = None;
+        } else {
+            self.pick(1);
+        }
+        true
+    }
+
+    /// Takes the last piece dropped back, to be moved again. Returns false if there was none.
+    pub fn undo(&mut self) -> bool {
+        let Some(dropped) = self.dropped.pop() else {
+            return false;
+        };
+        self.board.remove_piece(self.pieces[dropped.piece].id);
+        self.selected = Some(dropped.piece);
+        self.orientation = dropped.orientation;
+        self.column = dropped.column;
+        self.line = dropped.line;
+        self.message = None;
+        true
+    }
+
+    /// Does what the key is for, returns false when the player wants to leave
+    pub fn handle(&mut self, key: Key) -> bool {
+        match key {
+            Key::Left | Key::Char('h') => self.move_by(-1, 0),
+            Key::Right | Key::Char('l') => self.move_by(1, 0),
+            Key::Up | Key::Char('k') => self.move_by(0, -1),
+            Key::Down | Key::Char('j') => self.move_by(0, 1),
+            Key::Char(' ') | Key::Char('r') => self.turn(1),
+            Key::Char('R') => self.turn(-1),
+            Key::Char('\t') | Key::Char('n') => self.pick(1),
+            Key::BackTab | Key::Char('p') => self.pick(-1),
+            Key::Char('\n') => {
+                self.drop_piece();
+            }
+            Key::Char('u') | Key::Backspace => {
+                self.undo();
+            }
+            Key::Char('q') | Key::Esc | Key::Ctrl('c') => return false,
+            _ => (),
+        }
+        true
+    }
+
+    // the board with the piece being moved over it, and what can be done, for a raw terminal
+    fn frame(&self, title: &str) -> String {
+        let colored = Colored::with_colors(&self.board, true);
+        let fits = self.fits();
+        let mut ghost: Vec<(usize, usize)> = Vec::new();
+        if let Some((_, piece)) = self.selected() {
+            for (j, row) in piece.pattern.iter().enumerate() {
+                for (i, &square) in row.iter().enumerate() {
+                    if square != '0' {
+                        ghost.push((self.column + i, self.line + j));
+                    }
+                }
+            }
+        }
+
+        let mut lines = vec![title.to_string(), String::new()];
+        for line in 0..self.board.height() {
+            for row in 0..CELL_HEIGHT {
+                let mut text = String::new();
+                for column in 0..self.board.width() {
+                    let Some((id, _)) = self.selected().filter(|_| ghost.contains(&(column, line)))
+                    else {
+                        text.push_str(&colored.cell(column, line, row));
+                        continue;
+                    };
+                    // the squares that are in the way are red
+                    let (red, green, blue) = if fits || self.board.cell(column, line) == '0' {
+                        piece::color(id)
+                    } else {
+                        (200, 30, 30)
+                    };
+                    let fill = "░".repeat(CELL_WIDTH);
+                    text.push_str(&format!("{}{fill}{}", Bg(Rgb(red, green, blue)), Bg(Reset)));
+                }
+                lines.push(text);
+            }
+        }
+        lines.push(String::new());
+
+        // the pieces left, the one being moved between brackets
+        let pieces: Vec<String> = self
+            .pieces
+            .iter()
+            .enumerate()
+            .map(|(index, piece)| match self.selected {
+                Some(selected) if selected == index => format!("[{}]", piece.id),
+                _ if self.is_dropped(index) => format!(" {Faint}{}{NoFaint} ", piece.id),
+                _ => format!(" {} ", piece.id),
+            })
+            .collect();
+        lines.push(pieces.concat());
+        lines.push(HELP.to_string());
+        lines.push(self.message.clone().unwrap_or_default());
+        lines.join("\r\n")
+    }
+}
+
+/// Lets the player solve the board on the terminal until every cell is covered or they quit,
+/// `title` is written above the board. Returns whether the board was solved.
+pub fn play(board: Board, pieces: Vec<Piece>, title: &str) -> io::Result<bool> {
+    let mut game = Game::new(board, pieces);
+    let mut screen = io::stdout().into_raw_mode()?.into_alternate_screen()?;
+    let mut keys = io::stdin().keys();
+    let _cursor = HiddenCursor::new()?;
+
+    let solved = loop {
+        write!(
+            screen,
+            "{}{}{}",
+            termion::clear::All,
+            termion::cursor::Goto(1, 1),
+            game.frame(title)
+        )?;
+        if game.is_won() {
+            write!(screen, "\r\n\r\nSolved! Press any key to leave.")?;
+            screen.flush()?;
+            keys.next();
+            break true;
+        }
+        screen.flush()?;
+        let key = match keys.next() {
+            Some(key) => key?,
+            // nothing more to read
+            None => break false,
+        };
+        if !game.handle(key) {
+            break false;
+        }
+    };
+    Ok(solved)
+}
+
+// hides the cursor until it is dropped, so that it comes back however the game ends
+struct HiddenCursor;
+
+impl HiddenCursor {
+    fn new() -> io::Result<HiddenCursor> {
+        write!(io::stdout(), "{}", termion::cursor::Hide)?;
+        Ok(HiddenCursor)
+    }
+}
+
+impl Drop for HiddenCursor {
+    fn drop(&mut self) {
+        // nothing more can be done when the terminal is gone
+        let mut stdout = io::stdout();
+        let _ = write!(stdout, "{}", termion::cursor::Show);
+        let _ = stdout.flush();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::output;
+    use crate::piece::all_pieces;
+    use crate::solver::{Backtracking, Solver};
+
+    fn game() -> Game {
+        let mut b = Board::new();
+        b.set_date(1, 1, 1).unwrap();
+        Game::new(b, all_pieces())
+    }
+
+    #[test]
+    fn solve_with_the_keys() {
+        let mut game = game();
+        let solution = Backtracking::default()
+            .solve_first(game.board(), &all_pieces())
+            .unwrap();
+        for placement in output::placements(&solution, &all_pieces()) {
+            while game.selected().unwrap().0 != placement.id {
+                assert!(game.handle(Key::Char('\t')));
+            }
+            for _ in 0..placement.orientation {
+                game.handle(Key::Char(' '));
+            }
+            for _ in 0..10 {
+                game.handle(Key::Left);
+                game.handle(Key::Up);
+            }
+            for _ in 0..placement.column {
+                game.handle(Key::Right);
+            }
+            for _ in 0..placement.line {
+                game.handle(Key::Down);
+            }
+            assert_eq!(game.position(), (placement.column, placement.line));
+            assert!(game.fits());
+            game.handle(Key::Char('\n'));
+            assert_eq!(game.message(), None);
+        }
+        assert!(game.is_won());
+        assert_eq!(game.board(), &solution);
+        assert_eq!(game.selected(), None);
+        assert!(!game.handle(Key::Char('q')));
+    }
+
+    #[test]
+    fn collisions_and_undo() {
+        let mut game = game();
+        let empty = game.board().clone();
+        // the t over JAN, which is part of the date
+        assert!(!game.fits());
+        assert!(!game.drop_piece());
+        assert_eq!(game.message(), Some("t does not fit there"));
+        assert_eq!(game.board(), &empty);
+        assert!(game.frame("").contains(&Bg(Rgb(200, 30, 30)).to_string()));
+
+        game.move_by(1, 0);
+        assert!(game.fits());
+        assert!(game.drop_piece());
+        assert_eq!(game.message(), None);
+        assert_eq!(game.selected().unwrap().0, 'u');
+        // the u over the t
+        assert!(!game.drop_piece());
+
+        assert!(game.undo());
+        assert_eq!(game.board(), &empty);
+        assert_eq!(game.selected().unwrap().0, 't');
+        assert_eq!(game.position(), (1, 0));
+        assert!(!game.undo());
+
+        // the piece never leaves the board
+        game.move_by(100, 100);
+        assert_eq!(game.position(), (6, 3));
+        game.handle(Key::BackTab);
+        assert_eq!(game.selected().unwrap().0, 'f');
+    }
+}
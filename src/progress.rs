@@ -0,0 +1,156 @@
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crate::bitboard::{BitBoard, Bits};
+
+// looking at the clock at every node would slow the search down
+const NODES_BETWEEN_CLOCK_CHECKS: u64 = 1024;
+const BAR_WIDTH: usize = 30;
+
+/// What to show on the terminal while a search is running
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Progress {
+    /// Nothing at all
+    #[default]
+    Quiet,
+    /// One line with a bar, the nodes explored, the solutions found and the time spent so far
+    Bar,
+    /// The board as the search goes, redrawn at most `fps` times per second
+    Live {
+        /// Frames per second
+        fps: u32,
+    },
+}
+
+/// Keeps track of a search and shows it as asked by a [`Progress`], on stderr so that the
+/// solutions printed on stdout can be redirected
+pub struct Reporter {
+    progress: Progress,
+    start: Instant,
+    last_frame: Option<Instant>,
+    // how much of the search tree had been explored at the last frame
+    done: f64,
+    nodes: u64,
+    solutions: usize,
+}
+
+impl Reporter {
+    /// Starts timing the search
+    pub fn new(progress: Progress) -> Reporter {
+        Reporter {
+            progress,
+            start: Instant::now(),
+            last_frame: None,
+            done: 0.0,
+            nodes: 0,
+            solutions: 0,
+        }
+    }
+
+    /// Counts a node of the search, returns true when it is time to [`Reporter::show`] it
+    pub fn tick(&mut self) -> bool {
+        self.nodes += 1;
+        let interval = match self.progress {
+            Progress::Quiet => return false,
+            // the bar is cheap, but there is no point in going faster than the eye
+            Progress::Bar => Duration::from_millis(100),
+            Progress::Live { fps } => Duration::from_secs(1) / fps.max(1),
+        };
+        match self.last_frame {
+            None => true,
+            Some(_) if !self.nodes.is_multiple_of(NODES_BETWEEN_CLOCK_CHECKS) => false,
+            Some(last) => last.elapsed() >= interval,
+        }
+    }
+
+    /// Counts a solution
+    pub fn solution(&mut self) {
+        self.solutions += 1;
+    }
+
+    /// Draws the search as it is, `done` is how much of the search tree has been explored,
+    /// between 0 and 1
+    pub fn show<B: Bits>(&mut self, bits: &BitBoard<B>, done: f64) {
+        self.done = done;
+        let mut stderr = io::stderr().lock();
+        // nothing to do if the terminal is gone
+        let _ = match self.progress {
+            Progress::Quiet => return,
+            Progress::Bar => write!(stderr, "\r{}", self.bar()),
+            Progress::Live { .. } => writeln!(
+                stderr,
+                "{}{}{bits}{}",
+                termion::clear::All,
+                termion::cursor::Goto(1, 1),
+                self.bar()
+            ),
+        };
+        let _ = stderr.flush();
+        self.last_frame = Some(Instant::now());
+    }
+
+    fn bar(&self) -> String {
+        let done = self.done;
+        let filled = ((done.clamp(0.0, 1.0) * BAR_WIDTH as f64) as usize).min(BAR_WIDTH);
+        format!(
+            "[{}{}] {:>3}% {} nodes, {} solutions, {:.1} s",
+            "#".repeat(filled),
+            " ".repeat(BAR_WIDTH - filled),
+            (done * 100.0) as u32,
+            self.nodes,
+            self.solutions,
+            self.start.elapsed().as_secs_f64()
+        )
+    }
+}
+
+impl Drop for Reporter {
+    // leaves the last counts on the terminal, or clears the board for whatever comes next
+    fn drop(&mut self) {
+        if self.last_frame.is_none() {
+            return;
+        }
+        let _ = match self.progress {
+            Progress::Quiet => return,
+            Progress::Bar => writeln!(io::stderr(), "\r{}", self.bar()),
+            Progress::Live { .. } => write!(
+                io::stderr(),
+                "{}{}",
+                termion::clear::All,
+                termion::cursor::Goto(1, 1)
+            ),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn throttled() {
+        let mut quiet = Reporter::new(Progress::Quiet);
+        assert!(!(0..5000).any(|_| quiet.tick()));
+        assert_eq!(quiet.nodes, 5000);
+
+        let mut live = Reporter::new(Progress::Live { fps: 1 });
+        assert!(live.tick());
+        live.last_frame = Some(Instant::now());
+        // not a second yet
+        assert!(!(0..5000).any(|_| live.tick()));
+
+        live.last_frame = Some(Instant::now() - Duration::from_secs(2));
+        assert!((0..NODES_BETWEEN_CLOCK_CHECKS).any(|_| live.tick()));
+    }
+
+    #[test]
+    fn bar() {
+        let mut reporter = Reporter::new(Progress::Bar);
+        reporter.tick();
+        reporter.solution();
+        reporter.done = 0.5;
+        let bar = reporter.bar();
+        assert!(bar.starts_with(&format!("[{}{}]  50%", "#".repeat(15), " ".repeat(15))));
+        assert!(bar.contains("1 nodes, 1 solutions"));
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use crate::layout::BoardLayout;
+use crate::piece::{Piece, Rules};
+use crate::piece_set::PieceSet;
+
+/// The calendars the solver knows about, each with its board and its pieces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Puzzle {
+    /// 9x6 calendar with the week days and ten pentominoes
+    #[default]
+    Weekday,
+    /// 7x7 calendar with only the months and the days, seven pentominoes and a 2x3 rectangle
+    APuzzleADay,
+}
+
+impl Puzzle {
+    /// Where the months, days and week days are
+    pub fn layout(self) -> Arc<BoardLayout> {
+        match self {
+            Puzzle::Weekday => BoardLayout::weekday(),
+            Puzzle::APuzzleADay => BoardLayout::a_puzzle_a_day(),
+        }
+    }
+
+    /// The pieces that go on the board, each drawn once
+    pub fn piece_set(self) -> PieceSet {
+        match self {
+            Puzzle::Weekday => PieceSet::weekday(),
+            Puzzle::APuzzleADay => PieceSet::a_puzzle_a_day(),
+        }
+    }
+
+    /// The pieces that go on the board, turned as the rules allow
+    pub fn pieces(self, rules: Rules) -> Vec<Piece> {
+        self.piece_set().pieces(rules)
+    }
+
+    /// How the pieces are turned when nothing else is asked: the wooden pieces of the weekday
+    /// calendar have a top side, the others do not
+    pub fn rules(self) -> Rules {
+        match self {
+            Puzzle::Weekday => Rules::OneSided,
+            Puzzle::APuzzleADay => Rules::Free,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::board::Board;
+    use crate::dlx::Dlx;
+    use crate::solver::{Backtracking, Solver};
+    use chrono::NaiveDate;
+
+    fn board(puzzle: Puzzle, date: NaiveDate) -> Board {
+        let mut b = Board::from_layout(puzzle.layout());
+        b.set_date_from(date).unwrap();
+        b
+    }
+
+    #[test]
+    fn pieces_cover_the_board() {
+        for puzzle in [Puzzle::Weekday, Puzzle::APuzzleADay] {
+            let piece_set = puzzle.piece_set();
+            assert!(piece_set.check_area(&puzzle.layout()).is_ok(), "{puzzle:?}");
+            let area: usize = puzzle.pieces(puzzle.rules()).iter().map(Piece::size).sum();
+            assert_eq!(area, piece_set.area(), "{puzzle:?}");
+        }
+    }
+
+    #[test]
+    fn a_puzzle_a_day() {
+        // every day of the year can be solved, 2024 has a 29th of February
+        let pieces = Puzzle::APuzzleADay.pieces(Rules::Free);
+        let first_day = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        for date in first_day.iter_days().take(366) {
+            let b = board(Puzzle::APuzzleADay, date);
+            let solution = Backtracking::default().solve_first(&b, &pieces);
+            assert!(solution.is_some(), "{date}");
+            assert!(solution.unwrap().is_full());
+        }
+    }
+
+    #[test]
+    fn a_puzzle_a_day_counts() {
+        // the fewest and the most solutions of the year
+        let pieces = Puzzle::APuzzleADay.pieces(Rules::Free);
+        for (month, day, solutions) in [(10, 6, 7), (1, 25, 216)] {
+            let b = board(
+                Puzzle::APuzzleADay,
+                NaiveDate::from_ymd_opt(2024, month, day).unwrap(),
+            );
+            assert_eq!(Backtracking::default().count(&b, &pieces), solutions);
+            assert_eq!(Dlx.count(&b, &pieces), solutions);
+        }
+    }
+}
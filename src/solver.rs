@@ -0,0 +1,492 @@
+use chrono::NaiveDate;
+
+use crate::bitboard::{BitBoard, Bits, PieceMasks};
+use crate::progress::{Progress, Reporter};
+use crate::{board, piece};
+
+/// Once there are that many pieces left, the searches check that the free areas of the board
+/// can still be filled before going further
+pub const CHECK_FOR_SOLVABILITY_THRESH: usize = 5;
+
+/// A way of finding every placement of `pieces` that fills the free cells of a board
+pub trait Solver {
+    /// Calls on_solution with each solution as soon as it is found, on_solution returns false
+    /// to stop the search. Returns false if the search was stopped before the end.
+    fn for_each_solution(
+        &self,
+        b: &board::Board,
+        pieces: &[piece::Piece],
+        on_solution: &mut dyn FnMut(&board::Board) -> bool,
+    ) -> bool;
+
+    /// Every solution
+    fn solve_all(&self, b: &board::Board, pieces: &[piece::Piece]) -> Vec<board::Board> {
+        self.solve_up_to(b, pieces, usize::MAX)
+    }
+
+    /// The first solution found, if there is one
+    fn solve_first(&self, b: &board::Board, pieces: &[piece::Piece]) -> Option<board::Board> {
+        self.solve_up_to(b, pieces, 1).pop()
+    }
+
+    /// The first `limit` solutions found
+    fn solve_up_to(
+        &self,
+        b: &board::Board,
+        pieces: &[piece::Piece],
+        limit: usize,
+    ) -> Vec<board::Board> {
+        let mut solutions = Vec::new();
+        if limit > 0 {
+            self.for_each_solution(b, pieces, &mut |s| {
+                solutions.push(s.clone());
+                solutions.len() < limit
+            });
+        }
+        solutions
+    }
+
+    /// How many solutions there are
+    fn count(&self, b: &board::Board, pieces: &[piece::Piece]) -> usize {
+        let mut count = 0;
+        self.for_each_solution(b, pieces, &mut |_| {
+            count += 1;
+            true
+        });
+        count
+    }
+}
+
+/// Fills the first free space of the board with each piece that fits, one after the other
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Backtracking {
+    /// What to show while searching
+    pub progress: Progress,
+}
+
+impl Backtracking {
+    /// Iterator over the solutions, each one is only looked for when the previous one has been
+    /// consumed
+    pub fn solutions(&self, b: &board::Board, pieces: &[piece::Piece]) -> Solutions {
+        // a u64 is quite a bit faster, when the board fits in it
+        let search = if b.width() * b.height() <= u64::CELLS {
+            Search::Small(Words::new(b, pieces, self.progress))
+        } else {
+            Search::Large(Words::new(b, pieces, self.progress))
+        };
+        Solutions { search }
+    }
+
+    /// Counts the solutions and where they put each piece, without building any board
+    pub fn histogram(&self, b: &board::Board, pieces: &[piece::Piece]) -> Histogram {
+        let mut solutions = self.solutions(b, pieces);
+        let cells = b.width() * b.height();
+        let mut histogram = Histogram {
+            pieces: pieces.iter().map(|p| p.id).collect(),
+            width: b.width(),
+            placements: pieces
+                .iter()
+                .map(|p| vec![vec![0; cells]; p.possible_pieces.len()])
+                .collect(),
+            solutions: 0,
+        };
+
+        while solutions.find_next() {
+            histogram.solutions += 1;
+            for step in solutions.stack() {
+                histogram.placements[step.piece][step.orientation][step.cell] += 1;
+            }
+        }
+        histogram
+    }
+}
+
+impl Solver for Backtracking {
+    fn for_each_solution(
+        &self,
+        b: &board::Board,
+        pieces: &[piece::Piece],
+        on_solution: &mut dyn FnMut(&board::Board) -> bool,
+    ) -> bool {
+        for solution in self.solutions(b, pieces) {
+            if !on_solution(&solution) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn count(&self, b: &board::Board, pieces: &[piece::Piece]) -> usize {
+        let mut solutions = self.solutions(b, pieces);
+        let mut count = 0;
+        while solutions.find_next() {
+            count += 1;
+        }
+        count
+    }
+}
+
+/// How many solutions put each piece at each place, see [`Backtracking::histogram`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    /// Ids of the pieces, in the order they were given to the solver
+    pub pieces: Vec<char>,
+    /// Number of columns of the board
+    pub width: usize,
+    /// `placements[piece][orientation][line * width + column]` is the number of solutions where
+    /// that orientation of that piece has the first square of its top line on that cell. Pieces
+    /// and orientations are in the same order as `pieces` and [`Piece::possible_pieces`].
+    ///
+    /// [`Piece::possible_pieces`]: crate::Piece::possible_pieces
+    pub placements: Vec<Vec<Vec<usize>>>,
+    /// Number of solutions
+    pub solutions: usize,
+}
+
+// what has been tried so far to cover the first free space at one level of the search
+struct Step {
+    // the first free space, that every piece tried at this level covers
+    cell: usize,
+    piece: usize,
+    orientation: usize,
+    // whether pieces[piece][orientation] is on the board right now
+    placed: bool,
+}
+
+impl Step {
+    fn new(cell: usize) -> Step {
+        Step {
+            cell,
+            piece: 0,
+            orientation: 0,
+            placed: false,
+        }
+    }
+}
+
+/// The solutions of the [`Backtracking`], see [`Backtracking::solutions`]
+pub struct Solutions {
+    search: Search,
+}
+
+enum Search {
+    Small(Words<u64>),
+    Large(Words<u128>),
+}
+
+impl Solutions {
+    // see Words::find_next
+    fn find_next(&mut self) -> bool {
+        match &mut self.search {
+            Search::Small(words) => words.find_next(),
+            Search::Large(words) => words.find_next(),
+        }
+    }
+
+    fn stack(&self) -> &[Step] {
+        match &self.search {
+            Search::Small(words) => &words.stack,
+            Search::Large(words) => &words.stack,
+        }
+    }
+
+    fn to_board(&self) -> board::Board {
+        match &self.search {
+            Search::Small(words) => words.bits.to_board(),
+            Search::Large(words) => words.bits.to_board(),
+        }
+    }
+}
+
+// the search itself, on a board kept in that kind of word
+struct Words<B: Bits> {
+    reporter: Reporter,
+    bits: BitBoard<B>,
+    pieces: Vec<Vec<PieceMasks<B>>>,
+    used: Vec<bool>,
+    stack: Vec<Step>,
+}
+
+impl<B: Bits> Words<B> {
+    fn new(b: &board::Board, pieces: &[piece::Piece], progress: Progress) -> Words<B> {
+        let bits = BitBoard::from(b);
+        // every orientation of every piece, shifted on every cell of the board only once
+        let pieces: Vec<Vec<PieceMasks<B>>> = pieces
+            .iter()
+            .map(|p| {
+                p.possible_pieces
+                    .iter()
+                    .map(|o| PieceMasks::new(p.id, o, bits.width(), bits.height()))
+                    .collect()
+            })
+            .collect();
+
+        let stack = match bits.first_free_cell() {
+            Some(cell) => vec![Step::new(cell)],
+            None => Vec::new(),
+        };
+        Words {
+            reporter: Reporter::new(progress),
+            used: vec![false; pieces.len()],
+            bits,
+            pieces,
+            stack,
+        }
+    }
+
+    // moves on to the next solution, which is left on self.bits, returns false when there are
+    // no more
+    fn find_next(&mut self) -> bool {
+        while let Some(depth) = self.stack.len().checked_sub(1) {
+            let step = &mut self.stack[depth];
+            if step.placed {
+                self.bits
+                    .remove_piece(self.pieces[step.piece][step.orientation].id);
+                self.used[step.piece] = false;
+                step.placed = false;
+                step.orientation += 1;
+            }
+
+            while step.piece < self.pieces.len() {
+                if self.used[step.piece] || step.orientation >= self.pieces[step.piece].len() {
+                    step.piece += 1;
+                    step.orientation = 0;
+                    continue;
+                }
+                if !self.bits.place(&self.pieces[step.piece][step.orientation]) {
+                    step.orientation += 1;
+                    continue;
+                }
+
+                // pieces left once this one is on the board
+                let left = self.pieces.len() - depth - 1;
+                let current = step.piece;
+                let sizes = self
+                    .pieces
+                    .iter()
+                    .zip(&self.used)
+                    .enumerate()
+                    .filter(|&(p, (_, &used))| !used && p != current)
+                    .map(|(_, (orientations, _))| orientations[0].size);
+                if !self.bits.is_full()
+                    && left < CHECK_FOR_SOLVABILITY_THRESH
+                    && !self.bits.is_solvable_with(sizes)
+                {
+                    self.bits
+                        .remove_piece(self.pieces[step.piece][step.orientation].id);
+                    step.orientation += 1;
+                    continue;
+                }
+
+                step.placed = true;
+                self.used[step.piece] = true;
+                break;
+            }
+
+            if !step.placed {
+                // nothing else fits on the first free space, back to the previous piece
+                self.stack.pop();
+            } else {
+                if self.reporter.tick() {
+                    self.reporter.show(&self.bits, self.done());
+                }
+                match self.bits.first_free_cell() {
+                    Some(cell) => self.stack.push(Step::new(cell)),
+                    None => {
+                        // the board is full, the piece is taken off the next time we are called
+                        self.reporter.solution();
+                        return true;
+                    }
+                }
+            }
+        }
+        self.reporter.show(&self.bits, 1.0);
+        false
+    }
+
+    // a guess of how much of the search tree has been explored, from how far each of the first
+    // levels has gone through its pieces
+    fn done(&self) -> f64 {
+        let total: usize = self.pieces.iter().map(Vec::len).sum();
+        let mut done = 0.0;
+        let mut share = 1.0;
+        for step in self.stack.iter().take(3) {
+            let tried: usize = self.pieces[..step.piece]
+                .iter()
+                .map(Vec::len)
+                .sum::<usize>()
+                + step.orientation;
+            done += share * tried as f64 / total as f64;
+            share /= total as f64;
+        }
+        done
+    }
+}
+
+impl Iterator for Solutions {
+    type Item = board::Board;
+
+    fn next(&mut self) -> Option<board::Board> {
+        self.find_next().then(|| self.to_board())
+    }
+}
+
+/// Every solution for a date, looked for one after the other as the iterator is consumed
+pub fn solutions_for(date: NaiveDate) -> Solutions {
+    Backtracking::default().solutions(&board::Board::for_date(date), &piece::all_pieces())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::layout::BoardLayout;
+    use crate::piece_set::PieceSet;
+
+    fn board_for(week_day: u8, day: u8, month: u8) -> board::Board {
+        let mut board = board::Board::new();
+        board.set_date(week_day, day, month).unwrap();
+        board
+    }
+
+    #[test]
+    fn number_of_solutions() {
+        let solver = Backtracking::default();
+        let pieces = piece::all_pieces();
+        assert_eq!(solver.solve_all(&board_for(1, 1, 1), &pieces).len(), 15);
+        assert_eq!(solver.count(&board_for(1, 1, 1), &pieces), 15);
+        assert_eq!(solver.count(&board_for(6, 2, 3), &pieces), 8);
+        // Monday 5th of February cannot be solved
+        assert_eq!(solver.solve_first(&board_for(1, 5, 2), &pieces), None);
+    }
+
+    #[test]
+    fn free_pieces() {
+        let solver = Backtracking::default();
+        let pieces = piece::all_pieces_with(piece::Rules::Free);
+        // flipping the pieces over gives many more solutions, even for Monday 5th of February
+        assert_eq!(solver.count(&board_for(1, 1, 1), &pieces), 527);
+        assert_eq!(solver.count(&board_for(1, 5, 2), &pieces), 145);
+    }
+
+    #[test]
+    fn larger_than_a_u64() {
+        // the calendar with an extra blocked column and line has 70 cells
+        let padded: String = include_str!("../layouts/weekday.txt")
+            .lines()
+            .map(|line| format!("{line} #\n"))
+            .chain(std::iter::once("# ".repeat(10)))
+            .collect();
+        let mut b = board::Board::from_layout(padded.parse::<BoardLayout>().unwrap());
+        assert_eq!(b.width() * b.height(), 70);
+        b.set_date(1, 1, 1).unwrap();
+        let solver = Backtracking::default();
+        let pieces = piece::all_pieces();
+        assert_eq!(solver.count(&b, &pieces), 15);
+        assert_eq!(solver.solve_first(&b, &pieces).unwrap().width(), 10);
+        assert!(b.is_solvable_with(pieces.iter().map(piece::Piece::size)));
+    }
+
+    #[test]
+    fn larger_pieces() {
+        // three heptominoes, the last one is 6 squares wide
+        let set: PieceSet = "aaaaaaa\n\nbbbbbbb\n\ncccc..\n...ccc".parse().unwrap();
+        let pieces = set.pieces(piece::Rules::OneSided);
+        let mut b = board::Board::with_size(7, 4).unwrap();
+        for (column, line) in [(4, 2), (5, 2), (6, 2), (0, 3), (1, 3), (2, 3), (6, 3)] {
+            b.set_cell(column, line, 'X');
+        }
+        // the straight pieces go on the first two lines, either one above the other
+        assert_eq!(Backtracking::default().count(&b, &pieces), 2);
+        assert_eq!(crate::Dlx.count(&b, &pieces), 2);
+    }
+
+    #[test]
+    fn limit() {
+        let solver = Backtracking::default();
+        let board = board_for(1, 1, 1);
+        let pieces = piece::all_pieces();
+
+        let all = solver.solve_all(&board, &pieces);
+        let some = solver.solve_up_to(&board, &pieces, 3);
+        assert_eq!(some.len(), 3);
+        assert_eq!(some[..], all[..3]);
+        assert_eq!(solver.solve_first(&board, &pieces).as_ref(), all.first());
+        assert!(solver.solve_up_to(&board, &pieces, 0).is_empty());
+    }
+
+    #[test]
+    fn stop_streaming() {
+        let mut seen = 0;
+        let finished = Backtracking::default().for_each_solution(
+            &board_for(1, 1, 1),
+            &piece::all_pieces(),
+            &mut |s| {
+                assert!(s.is_full());
+                seen += 1;
+                seen < 2
+            },
+        );
+        assert!(!finished);
+        assert_eq!(seen, 2);
+    }
+
+    #[test]
+    fn lazy_solutions() {
+        let monday = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let expected = Backtracking::default().solve_all(&board_for(1, 1, 1), &piece::all_pieces());
+        assert_eq!(solutions_for(monday).collect::<Vec<_>>(), expected);
+        assert_eq!(
+            solutions_for(monday).take(2).collect::<Vec<_>>(),
+            expected[..2]
+        );
+
+        // Monday 5th of February 2024 cannot be solved
+        let february = NaiveDate::from_ymd_opt(2024, 2, 5).unwrap();
+        assert_eq!(solutions_for(february).next(), None);
+
+        let mut solutions = solutions_for(monday);
+        assert_eq!(solutions.by_ref().count(), 15);
+        assert_eq!(solutions.next(), None);
+    }
+
+    #[test]
+    fn histogram() {
+        let solver = Backtracking::default();
+        let board = board_for(1, 1, 1);
+        let pieces = piece::all_pieces();
+        let histogram = solver.histogram(&board, &pieces);
+
+        assert_eq!(histogram.solutions, 15);
+        assert_eq!(
+            histogram.pieces,
+            pieces.iter().map(|p| p.id).collect::<Vec<_>>()
+        );
+        for (p, orientations) in histogram.placements.iter().enumerate() {
+            assert_eq!(orientations.len(), pieces[p].possible_pieces.len());
+            // every piece is somewhere in every solution
+            let total: usize = orientations.iter().flatten().sum();
+            assert_eq!(total, 15);
+        }
+
+        // check a few against the boards themselves
+        let solutions = solver.solve_all(&board, &pieces);
+        let width = board.width();
+        assert_eq!(histogram.width, width);
+        for (p, id) in pieces.iter().map(|p| p.id).enumerate() {
+            for cell in 0..width * board.height() {
+                let first_square = solutions
+                    .iter()
+                    .filter(|s| {
+                        s.cell(cell % width, cell / width) == id
+                            && (0..cell).all(|c| s.cell(c % width, c / width) != id)
+                    })
+                    .count();
+                let counted: usize = histogram.placements[p]
+                    .iter()
+                    .map(|cells| cells[cell])
+                    .sum();
+                assert_eq!(counted, first_square);
+            }
+        }
+    }
+}
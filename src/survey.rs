@@ -0,0 +1,265 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{Month, Weekday};
+
+use crate::board;
+use crate::date::Date;
+use crate::layout::BoardLayout;
+
+/// One (week day, day, month) combination the board accepts
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// 1 for Monday, 7 for Sunday, None when the calendar has no week days
+    pub week_day: Option<u8>,
+    /// Day of the month
+    pub day: u8,
+    /// 1 for January, 12 for December
+    pub month: u8,
+    /// How many solutions were found
+    pub solutions: usize,
+}
+
+impl Entry {
+    /// Feb 30th, Apr 31st... are on the board but never on a calendar
+    pub fn is_real_date(&self) -> bool {
+        Date::new(self.week_day.unwrap_or(1), self.day, self.month).is_ok()
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if let Some(week_day) = self.week_day {
+            let week_day = Weekday::try_from(week_day - 1).expect("week days go from 1 to 7");
+            write!(f, "{week_day} ")?;
+        }
+        let month = Month::try_from(self.month).expect("months go from 1 to 12");
+        write!(f, "{} {}", self.day, &month.name()[..3])
+    }
+}
+
+/// Every combination accepted by [`Date::any_combination`], without the week days when the
+/// calendar does not have them
+pub fn all_combinations(week_days: bool) -> impl Iterator<Item = (Option<u8>, u8, u8)> {
+    // 0 stands for no week day
+    let week_days = if week_days { 1..=7 } else { 0..=0 };
+    (1..=12).flat_map(move |month| {
+        let week_days = week_days.clone();
+        (1..=31).flat_map(move |day| {
+            week_days
+                .clone()
+                .map(move |week_day| ((week_day > 0).then_some(week_day), day, month))
+        })
+    })
+}
+
+/// Number of solutions over several dates
+#[derive(Debug, PartialEq)]
+pub struct Statistics {
+    /// Fewest solutions of a date
+    pub min: usize,
+    /// Most solutions of a date
+    pub max: usize,
+    /// Median number of solutions
+    pub median: f64,
+}
+
+/// Result of a survey
+pub struct Report {
+    /// Every combination, in the order of [`all_combinations`]
+    pub entries: Vec<Entry>,
+    /// How long the whole survey took
+    pub elapsed: Duration,
+    /// When set, the search stopped after that many solutions for each date
+    pub limit: Option<usize>,
+}
+
+/// Counts the solutions of every combination on a board with that layout with `count`, calling
+/// on_entry as soon as each one is done. Stops at the first combination the layout does not have.
+pub fn run(
+    layout: &Arc<BoardLayout>,
+    limit: Option<usize>,
+    mut count: impl FnMut(&board::Board) -> usize,
+    mut on_entry: impl FnMut(&Entry),
+) -> Result<Report, board::DateError> {
+    let now = Instant::now();
+    let mut entries = Vec::new();
+
+    for (week_day, day, month) in all_combinations(layout.has_week_days()) {
+        let mut board = board::Board::from_layout(layout.clone());
+        // the week day is left out by the board when it has none
+        // Feb 30th and the like are surveyed too
+        board.set_date_to(Date::any_combination(week_day.unwrap_or(1), day, month)?)?;
+        let solutions = count(&board);
+
+        let entry = Entry {
+            week_day,
+            day,
+            month,
+            solutions,
+        };
+        on_entry(&entry);
+        entries.push(entry);
+    }
+
+    Ok(Report {
+        entries,
+        elapsed: now.elapsed(),
+        limit,
+    })
+}
+
+impl Report {
+    /// Combinations without any solution, among the real dates or the impossible ones
+    pub fn unsolvable(&self, real_dates: bool) -> impl Iterator<Item = &Entry> {
+        self.entries
+            .iter()
+            .filter(move |e| e.solutions == 0 && e.is_real_date() == real_dates)
+    }
+
+    /// Statistics on the number of solutions, for the real dates or the impossible ones
+    pub fn statistics(&self, real_dates: bool) -> Option<Statistics> {
+        let mut counts: Vec<usize> = self
+            .entries
+            .iter()
+            .filter(|e| e.is_real_date() == real_dates)
+            .map(|e| e.solutions)
+            .collect();
+        if counts.is_empty() {
+            return None;
+        }
+        counts.sort_unstable();
+
+        let middle = counts.len() / 2;
+        let median = if counts.len().is_multiple_of(2) {
+            (counts[middle - 1] + counts[middle]) as f64 / 2.0
+        } else {
+            counts[middle] as f64
+        };
+
+        Some(Statistics {
+            min: counts[0],
+            max: counts[counts.len() - 1],
+            median,
+        })
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        for (real_dates, title) in [(true, "Calendar dates"), (false, "Impossible dates")] {
+            let total = self
+                .entries
+                .iter()
+                .filter(|e| e.is_real_date() == real_dates)
+                .count();
+            let unsolvable: Vec<String> = self
+                .unsolvable(real_dates)
+                .map(|e| e.to_string())
+                .collect();
+
+            writeln!(f, "{title}: {total}")?;
+            if let Some(stats) = self.statistics(real_dates) {
+                writeln!(
+                    f,
+                    "  solutions: min {}, max {}, median {}",
+                    stats.min, stats.max, stats.median
+                )?;
+            }
+            if unsolvable.is_empty() {
+                writeln!(f, "  all of them can be solved")?;
+            } else {
+                writeln!(f, "  {} cannot be solved: {}", unsolvable.len(), unsolvable.join(", "))?;
+            }
+        }
+        if let Some(limit) = self.limit {
+            writeln!(f, "Solutions were only counted up to {limit} per date")?;
+        }
+        write!(f, "Surveyed in {} s", self.elapsed.as_secs())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry(week_day: u8, day: u8, month: u8, solutions: usize) -> Entry {
+        Entry {
+            week_day: Some(week_day),
+            day,
+            month,
+            solutions,
+        }
+    }
+
+    #[test]
+    fn combinations() {
+        assert_eq!(all_combinations(true).count(), 7 * 31 * 12);
+        let real = all_combinations(true)
+            .filter(|&(w, d, m)| entry(w.unwrap(), d, m, 0).is_real_date())
+            .count();
+        assert_eq!(real, 7 * 366);
+        assert_eq!(all_combinations(false).count(), 31 * 12);
+        assert!(all_combinations(false).all(|(w, _, _)| w.is_none()));
+    }
+
+    #[test]
+    fn impossible_dates() {
+        assert!(entry(1, 29, 2, 0).is_real_date());
+        assert!(!entry(1, 30, 2, 0).is_real_date());
+        assert!(!entry(1, 31, 4, 0).is_real_date());
+        assert!(entry(1, 31, 12, 0).is_real_date());
+        assert_eq!(entry(4, 13, 8, 0).to_string(), "Thu 13 Aug");
+        let no_week_day = Entry {
+            week_day: None,
+            ..entry(4, 13, 8, 0)
+        };
+        assert_eq!(no_week_day.to_string(), "13 Aug");
+    }
+
+    #[test]
+    fn report() {
+        let report = Report {
+            entries: vec![
+                entry(1, 1, 1, 4),
+                entry(2, 1, 1, 0),
+                entry(3, 1, 1, 10),
+                entry(4, 1, 1, 7),
+                entry(1, 30, 2, 0),
+            ],
+            elapsed: Duration::ZERO,
+            limit: None,
+        };
+        assert_eq!(
+            report.statistics(true),
+            Some(Statistics {
+                min: 0,
+                max: 10,
+                median: 5.5
+            })
+        );
+        assert_eq!(
+            report.statistics(false),
+            Some(Statistics {
+                min: 0,
+                max: 0,
+                median: 0.0
+            })
+        );
+        assert_eq!(report.unsolvable(true).count(), 1);
+        assert_eq!(report.unsolvable(false).count(), 1);
+    }
+
+    #[test]
+    fn missing_label() {
+        let layout: BoardLayout = "JAN 1 2 3\n. . . .".parse().unwrap();
+        let mut counted = 0;
+        let report = run(&Arc::new(layout), None, |_| 0, |_| counted += 1);
+        assert!(matches!(
+            report,
+            Err(board::DateError::UnknownLabel(label)) if label == "4"
+        ));
+        assert_eq!(counted, 3);
+    }
+}
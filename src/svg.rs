@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+use std::{fs, io};
+
+use crate::board::Board;
+use crate::layout::Cell;
+use crate::piece;
+
+// size of a cell in pixels
+const CELL: usize = 40;
+// room around the board so that its outline is not cut
+const MARGIN: usize = 4;
+
+/// The board as an SVG picture: the cells of the calendar with their labels, and each piece
+/// filled with its [`piece::color`] and outlined. Only the labels of the cells no piece
+/// covers, the date on a solved board, can be seen.
+pub fn to_svg(b: &Board) -> String {
+    let width = b.width() * CELL + 2 * MARGIN;
+    let height = b.height() * CELL + 2 * MARGIN;
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    svg.push('\n');
+
+    let mut calendar = Vec::new();
+    let mut pieces: Vec<(char, Vec<(usize, usize)>)> = Vec::new();
+    for line in 0..b.height() {
+        for column in 0..b.width() {
+            let label = match b.layout().cell(column, line) {
+                Cell::Blocked => continue,
+                Cell::Free(label) => label,
+            };
+            calendar.push((column, line));
+            let (x, y) = corner(column, line);
+            writeln!(
+                svg,
+                r##"<rect x="{x}" y="{y}" width="{CELL}" height="{CELL}" fill="#f3ead7" stroke="#cbbd9e"/>"##
+            )
+            .unwrap();
+            if let Some(label) = label {
+                writeln!(
+                    svg,
+                    r##"<text x="{}" y="{}" font-family="sans-serif" font-size="12" text-anchor="middle" dominant-baseline="central" fill="#4a3f2b">{}</text>"##,
+                    x + CELL / 2,
+                    y + CELL / 2,
+                    escape(label)
+                )
+                .unwrap();
+            }
+
+            let id = b.cell(column, line);
+            if id == 'X' || id == '0' {
+                continue;
+            }
+            match pieces.iter_mut().find(|(other, _)| *other == id) {
+                Some((_, squares)) => squares.push((column, line)),
+                None => pieces.push((id, vec![(column, line)])),
+            }
+        }
+    }
+
+    for (id, squares) in &pieces {
+        let (red, green, blue) = piece::color(*id);
+        writeln!(
+            svg,
+            r##"<path d="{}" fill="#{red:02x}{green:02x}{blue:02x}" fill-rule="evenodd" stroke="#222222" stroke-width="3" stroke-linejoin="round"/>"##,
+            outline(squares)
+        )
+        .unwrap();
+    }
+    writeln!(
+        svg,
+        r##"<path d="{}" fill="none" stroke="#222222" stroke-width="6" stroke-linejoin="round"/>"##,
+        outline(&calendar)
+    )
+    .unwrap();
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Writes [`to_svg`] to a file
+pub fn save(b: &Board, path: impl AsRef<Path>) -> io::Result<()> {
+    fs::write(path, to_svg(b))
+}
+
+// top left corner of a cell in pixels
+fn corner(column: usize, line: usize) -> (usize, usize) {
+    (MARGIN + column * CELL, MARGIN + line * CELL)
+}
+
+// labels come from layout files and could have anything in them
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// the path around the squares, one loop for the outside and one for each hole
+fn outline(squares: &[(usize, usize)]) -> String {
+    // each side of a square that is not shared with another one, going clockwise around it
+    let mut sides: HashMap<(usize, usize), Vec<(usize, usize)>> = HashMap::new();
+    for &(column, line) in squares {
+        let has = |c: Option<usize>, l: Option<usize>| match (c, l) {
+            (Some(c), Some(l)) => squares.contains(&(c, l)),
+            _ => false,
+        };
+        let (left, right) = (column, column + 1);
+        let (top, bottom) = (line, line + 1);
+        if !has(Some(column), line.checked_sub(1)) {
+            sides.entry((left, top)).or_default().push((right, top));
+        }
+        if !has(Some(column + 1), Some(line)) {
+            sides.entry((right, top)).or_default().push((right, bottom));
+        }
+        if !has(Some(column), Some(line + 1)) {
+            sides
+                .entry((right, bottom))
+                .or_default()
+                .push((left, bottom));
+        }
+        if !has(column.checked_sub(1), Some(line)) {
+            sides.entry((left, bottom)).or_default().push((left, top));
+        }
+    }
+
+    let mut path = String::new();
+    // the smallest corner first so that the same squares always give the same path
+    let mut starts: Vec<(usize, usize)> = sides.keys().copied().collect();
+    starts.sort_unstable_by_key(|&(x, y)| (y, x));
+    for start in starts {
+        // squares touching by a corner give a corner with two sides going out of it
+        while sides.get(&start).is_some_and(|next| !next.is_empty()) {
+            let mut points = vec![start];
+            let mut point = start;
+            while let Some(next) = sides.get_mut(&point).and_then(Vec::pop) {
+                point = next;
+                points.push(point);
+            }
+            trace(&mut path, &points);
+        }
+    }
+    path
+}
+
+// adds a loop that ends where it starts to the path
+fn trace(path: &mut String, points: &[(usize, usize)]) {
+    // only keep the corners where the outline turns
+    let mut corners: Vec<(usize, usize)> = Vec::new();
+    for (index, &point) in points.iter().enumerate().take(points.len() - 1) {
+        let previous = points[(index + points.len() - 2) % (points.len() - 1)];
+        let next = points[index + 1];
+        let straight = (previous.0 == point.0 && point.0 == next.0)
+            || (previous.1 == point.1 && point.1 == next.1);
+        if !straight {
+            corners.push(point);
+        }
+    }
+    if !path.is_empty() {
+        path.push(' ');
+    }
+    for (index, &(x, y)) in corners.iter().enumerate() {
+        let (x, y) = corner(x, y);
+        let command = if index == 0 { 'M' } else { 'L' };
+        write!(path, "{command}{x},{y} ").unwrap();
+    }
+    path.push('Z');
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::piece::all_pieces;
+    use crate::solver::{Backtracking, Solver};
+
+    #[test]
+    fn outlines() {
+        assert_eq!(outline(&[(0, 0)]), "M4,4 L44,4 L44,44 L4,44 Z");
+        // an L: the corners where it turns, not every square
+        assert_eq!(
+            outline(&[(0, 0), (0, 1), (0, 2), (1, 2)]),
+            "M4,4 L44,4 L44,84 L84,84 L84,124 L4,124 Z"
+        );
+        // a ring has its hole as a second loop
+        let ring: Vec<(usize, usize)> = (0..3)
+            .flat_map(|line| (0..3).map(move |column| (column, line)))
+            .filter(|&square| square != (1, 1))
+            .collect();
+        assert_eq!(
+            outline(&ring),
+            "M4,4 L124,4 L124,124 L4,124 Z M44,44 L44,84 L84,84 L84,44 Z"
+        );
+    }
+
+    #[test]
+    fn solved_board() {
+        let mut b = Board::new();
+        b.set_date(1, 1, 1).unwrap();
+        let solution = Backtracking::default()
+            .solve_first(&b, &all_pieces())
+            .unwrap();
+        let svg = to_svg(&solution);
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="368" height="248""#)
+        );
+        assert!(svg.ends_with("</svg>\n"));
+        // one path per piece and one around the board
+        assert_eq!(svg.matches("<path").count(), 11);
+        let (red, green, blue) = piece::color('t');
+        assert!(svg.contains(&format!("#{red:02x}{green:02x}{blue:02x}")));
+        // every label is there, even if only the date can be seen
+        for label in ["JAN", "1", "31", "MON", "SUN"] {
+            assert!(svg.contains(&format!(">{label}</text>")), "{label}");
+        }
+        assert_eq!(escape("<a & b>"), "&lt;a &amp; b&gt;");
+    }
+}
@@ -0,0 +1,262 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::board::Board;
+use crate::piece::Piece;
+use crate::solver::Solver;
+
+/// A solution reduced to the cells each piece covers, without the ids of the pieces, so that
+/// solutions that only swap two pieces of the same shape are the same.
+///
+/// With [`Canonical::with_symmetries`], solutions that are turned or flipped versions of each
+/// other are the same as well, when the board looks the same once turned or flipped.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Canonical {
+    // the cells of each piece as line * width + column, each region sorted and then all of
+    // them sorted
+    regions: Vec<Vec<usize>>,
+}
+
+impl Canonical {
+    /// Only forgets which piece is which
+    pub fn new(solution: &Board) -> Canonical {
+        Canonical::turned(solution, |column, line| (column, line))
+    }
+
+    /// Also the same for every way of turning or flipping the board that leaves its blocked
+    /// cells where they are
+    pub fn with_symmetries(solution: &Board) -> Canonical {
+        let (width, height) = (solution.width(), solution.height());
+        // a quarter turn only keeps the board the same if it is square
+        let turns = if width == height { 8 } else { 4 };
+        (0..turns)
+            .filter(|&index| {
+                cells(solution).all(|(column, line)| {
+                    let (turned_column, turned_line) = turn(index, column, line, width, height);
+                    (solution.cell(column, line) == 'X')
+                        == (solution.cell(turned_column, turned_line) == 'X')
+                })
+            })
+            .map(|index| {
+                Canonical::turned(solution, |column, line| {
+                    turn(index, column, line, width, height)
+                })
+            })
+            .min()
+            .expect("the board is always the same as itself")
+    }
+
+    fn turned(solution: &Board, turn: impl Fn(usize, usize) -> (usize, usize)) -> Canonical {
+        let mut ids: Vec<char> = Vec::new();
+        let mut regions: Vec<Vec<usize>> = Vec::new();
+        for (column, line) in cells(solution) {
+            let id = solution.cell(column, line);
+            if id == 'X' || id == '0' {
+                continue;
+            }
+            let (column, line) = turn(column, line);
+            let cell = line * solution.width() + column;
+            match ids.iter().position(|&other| other == id) {
+                Some(index) => regions[index].push(cell),
+                None => {
+                    ids.push(id);
+                    regions.push(vec![cell]);
+                }
+            }
+        }
+        for region in &mut regions {
+            region.sort_unstable();
+        }
+        regions.sort_unstable();
+        Canonical { regions }
+    }
+}
+
+// the 8 ways to turn or flip a square, the first 4 keep any rectangle the same shape
+fn turn(index: usize, column: usize, line: usize, width: usize, height: usize) -> (usize, usize) {
+    match index {
+        0 => (column, line),
+        1 => (width - 1 - column, line),
+        2 => (column, height - 1 - line),
+        3 => (width - 1 - column, height - 1 - line),
+        4 => (line, column),
+        5 => (width - 1 - line, column),
+        6 => (line, width - 1 - column),
+        _ => (width - 1 - line, width - 1 - column),
+    }
+}
+
+// every (column, line) of the board
+fn cells(b: &Board) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (0..b.height()).flat_map(move |line| (0..b.width()).map(move |column| (column, line)))
+}
+
+/// Counts the solutions as they are found, and how many of them are really different
+#[derive(Debug, Default)]
+pub struct Distinct {
+    symmetries: bool,
+    seen: HashSet<Canonical>,
+    raw: usize,
+}
+
+impl Distinct {
+    /// Solutions are the same when they only swap pieces of the same shape, and also when they
+    /// are turned or flipped versions of each other if `symmetries` is set
+    pub fn new(symmetries: bool) -> Distinct {
+        Distinct {
+            symmetries,
+            ..Distinct::default()
+        }
+    }
+
+    /// Counts a solution, returns true if it is not the same as any of the previous ones
+    pub fn insert(&mut self, solution: &Board) -> bool {
+        self.raw += 1;
+        let canonical = if self.symmetries {
+            Canonical::with_symmetries(solution)
+        } else {
+            Canonical::new(solution)
+        };
+        self.seen.insert(canonical)
+    }
+
+    /// Counts the solutions `solver` finds until `limit` of them are really different, and calls
+    /// on_distinct with each of those. on_distinct returns false to stop earlier.
+    pub fn search(
+        &mut self,
+        solver: &dyn Solver,
+        b: &Board,
+        pieces: &[Piece],
+        limit: usize,
+        on_distinct: &mut dyn FnMut(&Board) -> bool,
+    ) {
+        if limit == 0 {
+            return;
+        }
+        solver.for_each_solution(b, pieces, &mut |s| {
+            // duplicates do not count towards the limit
+            if !self.insert(s) {
+                return true;
+            }
+            on_distinct(s) && self.distinct() < limit
+        });
+    }
+
+    /// Number of solutions counted
+    pub fn raw(&self) -> usize {
+        self.raw
+    }
+
+    /// Number of solutions that are really different
+    pub fn distinct(&self) -> usize {
+        self.seen.len()
+    }
+}
+
+impl fmt::Display for Distinct {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{} solutions, {} distinct", self.raw, self.distinct())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dlx::Dlx;
+    use crate::piece::{self, Rules};
+    use crate::piece_set::PieceSet;
+    use crate::solver::Backtracking;
+
+    fn count(b: &Board, pieces: &[piece::Piece], symmetries: bool) -> Distinct {
+        let mut distinct = Distinct::new(symmetries);
+        for solution in Backtracking::default().solve_all(b, pieces) {
+            distinct.insert(&solution);
+        }
+        distinct
+    }
+
+    #[test]
+    fn no_duplicates_from_the_backtracking() {
+        // no two pieces of the calendar have the same shape, every solution is different
+        for rules in [Rules::OneSided, Rules::Free] {
+            let pieces = piece::all_pieces_with(rules);
+            for (week_day, day, month) in [(1, 1, 1), (6, 2, 3), (4, 13, 8)] {
+                let mut b = Board::new();
+                b.set_date(week_day, day, month).unwrap();
+                let distinct = count(&b, &pieces, false);
+                assert_eq!(distinct.raw(), distinct.distinct(), "{rules:?}");
+            }
+        }
+
+        // the same orientation given twice does give the same solution twice, and they are
+        // seen as the same
+        let mut x = piece::Piece::new('x', &["0x0", "xxx", "0x0"], Rules::Free);
+        x.possible_pieces.push(x.possible_pieces[0].clone());
+        let mut b = Board::with_size(3, 3).unwrap();
+        for (column, line) in [(0, 0), (2, 0), (0, 2), (2, 2)] {
+            b.set_cell(column, line, 'X');
+        }
+        let solutions = Backtracking::default().solve_all(&b, &[x]);
+        assert_eq!(solutions.len(), 2);
+        assert_eq!(Canonical::new(&solutions[0]), Canonical::new(&solutions[1]));
+    }
+
+    #[test]
+    fn same_shapes() {
+        // two dominoes on a 2x2 square: either one on top, or either one on the left
+        let pieces = "aa\n\nbb".parse::<PieceSet>().unwrap().pieces(Rules::Free);
+        let b = Board::with_size(2, 2).unwrap();
+        let distinct = count(&b, &pieces, false);
+        assert_eq!((distinct.raw(), distinct.distinct()), (4, 2));
+        // and lying down is the same as standing up once the square is turned
+        let distinct = count(&b, &pieces, true);
+        assert_eq!((distinct.raw(), distinct.distinct()), (4, 1));
+        assert_eq!(distinct.to_string(), "4 solutions, 1 distinct");
+    }
+
+    #[test]
+    fn limit() {
+        // the second solution is the first one with the dominoes swapped, the limit skips it
+        let pieces = "c\n\nd\n\naa\n\nbb"
+            .parse::<PieceSet>()
+            .unwrap()
+            .pieces(Rules::Free);
+        let b = Board::with_size(2, 2).unwrap();
+        let all = Backtracking::default().solve_all(&b, &pieces);
+        let mut distinct = Distinct::new(false);
+        let mut found = Vec::new();
+        distinct.search(&Backtracking::default(), &b, &pieces, 2, &mut |s| {
+            found.push(s.clone());
+            true
+        });
+        assert_eq!(found, [all[0].clone(), all[2].clone()]);
+        assert_eq!((distinct.raw(), distinct.distinct()), (3, 2));
+
+        // stopping early
+        let mut distinct = Distinct::new(false);
+        distinct.search(&Backtracking::default(), &b, &pieces, 2, &mut |_| false);
+        assert_eq!((distinct.raw(), distinct.distinct()), (1, 1));
+    }
+
+    #[test]
+    fn turned_boards() {
+        // the pentominoes fill a 3x20 rectangle in 2 ways, each turned and flipped 4 ways
+        let pentominoes: PieceSet = include_str!("../pieces/pentominoes.txt").parse().unwrap();
+        let mut distinct = Distinct::new(true);
+        Dlx.for_each_solution(
+            &Board::with_size(20, 3).unwrap(),
+            &pentominoes.pieces(Rules::Free),
+            &mut |solution| {
+                distinct.insert(solution);
+                true
+            },
+        );
+        assert_eq!((distinct.raw(), distinct.distinct()), (8, 2));
+
+        // the calendar is not the same once turned, so nothing changes
+        let mut b = Board::new();
+        b.set_date(1, 1, 1).unwrap();
+        let distinct = count(&b, &piece::all_pieces(), true);
+        assert_eq!((distinct.raw(), distinct.distinct()), (15, 15));
+    }
+}